@@ -1,32 +1,58 @@
 #![allow(dead_code)]
 pub const KEY_NULL: i32 = 0;
+pub const KEY_STX: i32 = 2;
 pub const KEY_EOT: i32 = 4;
+pub const KEY_ENQ: i32 = 5;
+pub const KEY_ACK: i32 = 6;
 pub const KEY_BS: i32 = 8;
+pub const KEY_HT: i32 = 9;
 pub const KEY_LF: i32 = 10;
+pub const KEY_DC2: i32 = 18;
 pub const KEY_NAK: i32 = 21;
 pub const KEY_ETB: i32 = 27;
+pub const KEY_SPACE: i32 = 32;
 pub const KEY_ESC: i32 = 33;
 pub const KEY_FORWARD_SLASH: i32 = 47;
 pub const KEY_COLON: i32 = 58;
+pub const KEY_A_UPPER: i32 = 65;
+pub const KEY_B_UPPER: i32 = 66;
 pub const KEY_C_UPPER: i32 = 67;
+pub const KEY_D_UPPER: i32 = 68;
+pub const KEY_F_UPPER: i32 = 70;
+pub const KEY_J_UPPER: i32 = 74;
+pub const KEY_K_UPPER: i32 = 75;
 pub const KEY_N_UPPER: i32 = 78;
 pub const KEY_O_UPPER: i32 = 79;
 pub const KEY_P_UPPER: i32 = 80;
 pub const KEY_R_UPPER: i32 = 82;
+pub const KEY_T_UPPER: i32 = 84;
+pub const KEY_U_UPPER: i32 = 85;
+pub const KEY_V_UPPER: i32 = 86;
+pub const KEY_Z_UPPER: i32 = 90;
+pub const KEY_A_LOWER: i32 = 97;
 pub const KEY_B_LOWER: i32 = 98;
 pub const KEY_C_LOWER: i32 = 99;
 pub const KEY_D_LOWER: i32 = 100;
+pub const KEY_E_LOWER: i32 = 101;
+pub const KEY_F_LOWER: i32 = 102;
 pub const KEY_Q_LOWER: i32 = 113;
 pub const KEY_H_LOWER: i32 = 104;
+pub const KEY_I_LOWER: i32 = 105;
 pub const KEY_J_LOWER: i32 = 106;
 pub const KEY_K_LOWER: i32 = 107;
 pub const KEY_L_LOWER: i32 = 108;
+pub const KEY_M_LOWER: i32 = 109;
 pub const KEY_N_LOWER: i32 = 110;
+pub const KEY_O_LOWER: i32 = 111;
 pub const KEY_P_LOWER: i32 = 112;
+pub const KEY_R_LOWER: i32 = 114;
+pub const KEY_S_LOWER: i32 = 115;
 pub const KEY_T_LOWER: i32 = 116;
 pub const KEY_U_LOWER: i32 = 117;
+pub const KEY_V_LOWER: i32 = 118;
 pub const KEY_W_LOWER: i32 = 119;
 pub const KEY_Y_LOWER: i32 = 121;
+pub const KEY_Z_LOWER: i32 = 122;
 pub const KEY_DEL: i32 = 127;
 pub const KEY_ZERO: i32 = 48;
 pub const KEY_DOLLAR: i32 = 36;
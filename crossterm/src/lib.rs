@@ -18,6 +18,8 @@ pub static mut MAX_WIDTH: i32   = 0;
 pub static mut MAX_HEIGHT: i32  = 0;
 
 static HIGHLIGHT_COLOR: Color = Rgb { r: 50, g: 50, b: 50 };
+static MARK_COLOR: Color = Rgb { r: 0, g: 0, b: 120 };
+static MATCH_COLOR: Color = Rgb { r: 255, g: 200, b: 0 };
 
 pub fn screen_factory(size: ScreenSize, position: Position) -> Box<dyn DrawScreen> {
     Box::new(CrosstermWindow::new(size, position))
@@ -90,30 +92,14 @@ impl CrosstermWindow {
         (x, y)
     }
 
+    // Splits the text into lines of at most max_width characters.
     fn fit_line(&self, part: &str, max_width: usize) -> Vec<String> {
-        if part.len() < max_width {
-            return vec![part.to_owned()]
-        }
-
-        let mut lines: Vec<String> = vec![];
-
-        let first = &part[..max_width];
-        let mut rest = &part[max_width..];
+        let chars: Vec<char> = part.chars().collect();
 
-        lines.push(first.to_owned());
-
-        while rest.len() >= max_width {
-            let first = &rest[..max_width];
-            lines.push(first.to_owned());
-
-            rest = &rest[max_width..];
-        }
-
-        if !rest.is_empty() {
-            lines.push(rest.to_owned());
-        }
-
-        lines
+        chars
+            .chunks(max_width.max(1))
+            .map(|c| c.iter().collect())
+            .collect()
     }
 
     fn parse_line(&self, line: &Line) -> Vec<Vec<StyledContent<String>>> {
@@ -148,16 +134,23 @@ impl CrosstermWindow {
                             .with(foreground_color)
                             .on(background_color);
                     },
+                    Style::Marked => {
+                        output_str = output_str.on(MARK_COLOR);
+                    },
+                    Style::Match => {
+                        output_str = output_str.with(Color::Black).on(MATCH_COLOR);
+                    },
                     _ => { }
                 }
             }
 
-            let output = part.value.clone();
+            // Widths are counted in characters, as the text is not only ASCII.
+            let output: Vec<char> = part.value.chars().collect();
             let current_len = output.len();
             let previous_length: usize = line
                 .parts[..i]
                 .iter()
-                .map(|p| p.value.len())
+                .map(|p| p.value.chars().count())
                 .sum();
 
             // Checks if the current part is over the screen width.
@@ -166,16 +159,16 @@ impl CrosstermWindow {
             // If the current part is over the screen width, split it apart
             // and render the second part into the next line.
             if over_width {
-                let idx = current_len - ((previous_length + current_len) - self.width as usize);
+                let idx = (self.width as usize).saturating_sub(previous_length).min(current_len);
                 let style  = output_str.style().clone();
 
-                let first  = &output[..idx];
-                let second = &output[idx..];
-                styled_line.push(StyledContent::new(style, first.to_string()));
+                let first: String  = output[..idx].iter().collect();
+                let second: String = output[idx..].iter().collect();
+                styled_line.push(StyledContent::new(style, first));
 
                 // The current part might be over a line wide, so we need to keep
                 // splitting it until it fits.
-                let split_lines = self.fit_line(second, self.width as usize);
+                let split_lines = self.fit_line(&second, self.width as usize);
 
                 let content: Vec<StyledContent<String>> = split_lines
                     .iter()
@@ -279,7 +272,7 @@ impl DrawScreen for CrosstermWindow {
                 if cursor_line && last_part {
                     let line_length: usize = styled_line
                         .iter()
-                        .map(|p| p.content().len())
+                        .map(|p| p.content().chars().count())
                         .sum();
 
                     if line_length >= self.width  as usize {
@@ -382,16 +375,18 @@ impl DrawScreen for CrosstermWindow {
             match read().unwrap() {
                 Event::Key(KeyEvent { modifiers: KeyModifiers::CONTROL, code }) => {
                     match code {
-                        KeyCode::Char('d') => return 4,
-                        KeyCode::Char('u') => return 21,
+                        // Control characters as a terminal sends them, e.g. ctrl + d is 4.
+                        KeyCode::Char(c) if c.is_ascii_alphabetic() => return (c.to_ascii_lowercase() as u8 & 0x1f) as i32,
                         KeyCode::Char(c)   => return c as i32,
                         _ => { }
                     }
                 },
                 Event::Key(event) => {
                     match event.code {
+                        KeyCode::Tab     => return 9,
                         KeyCode::Enter   => return 10,
                         KeyCode::Esc     => return 27,
+                        KeyCode::Backspace => return 127,
                         KeyCode::Char(c) => return c as i32,
                         _ => {}
                     }
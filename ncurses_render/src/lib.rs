@@ -151,6 +151,18 @@ impl DrawScreen for CursesWindow {
                                 ncurses::A_BOLD()
                             );
                         },
+                        Style::Marked => {
+                            ncurses::wattron(
+                                self.curses_window,
+                                ncurses::A_REVERSE()
+                            );
+                        },
+                        Style::Match => {
+                            ncurses::wattron(
+                                self.curses_window,
+                                ncurses::A_STANDOUT()
+                            );
+                        },
                         Style::Painted (_, _) => { },
                         Style::Plain => { }
                     }
@@ -178,6 +190,18 @@ impl DrawScreen for CursesWindow {
                                 ncurses::A_BOLD()
                             );
                         },
+                        Style::Marked => {
+                            ncurses::wattroff(
+                                self.curses_window,
+                                ncurses::A_REVERSE()
+                            );
+                        },
+                        Style::Match => {
+                            ncurses::wattroff(
+                                self.curses_window,
+                                ncurses::A_STANDOUT()
+                            );
+                        },
                         Style::Painted (_, _) => { },
                         Style::Plain => { }
                     }
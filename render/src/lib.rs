@@ -1,10 +1,14 @@
 #![allow(dead_code)]
 
 use std::cmp::{Ordering};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
 use gitko_common::ascii_table::*;
 
+mod search;
+
+pub use search::{Filter, SearchFlags, SearchPattern};
+
 pub type KeyHandlers<T> = HashMap<i32, fn(&mut T, &mut Window) -> bool>;
 pub type ScreenFactory = fn(ScreenSize, Position) -> Box<dyn DrawScreen>;
 
@@ -100,6 +104,21 @@ pub struct Window {
     position: Position,
     pub cursor_position: Position,
 
+    // Indices of the marked lines, and the start of
+    // the visual range if one is being selected.
+    marks: BTreeSet<usize>,
+    visual_start: Option<usize>,
+
+    // The pattern searched for with move_next and move_prev.
+    search: Option<SearchPattern>,
+    // Whether the last move to a match went past an end of the lines.
+    search_wrapped: bool,
+
+    // The filter narrowing the lines down, and the indices of the
+    // shown lines in the order they are shown in, all of them if none.
+    filter: Option<Filter>,
+    rows: Vec<usize>,
+
     cursor_hidden: bool,
     screen: Box<dyn DrawScreen>
 }
@@ -112,6 +131,12 @@ impl Window {
 
             position: Position::default(),
             cursor_position: Position::default(),
+            marks: BTreeSet::new(),
+            visual_start: None,
+            search: None,
+            search_wrapped: false,
+            filter: None,
+            rows: vec![],
             cursor_hidden: false,
             screen: screen_factory(size, position)
         }
@@ -119,6 +144,11 @@ impl Window {
 
     pub fn set_lines(&mut self, lines: Vec<Line>) {
         self.lines = lines;
+
+        // Marks are line indices, which point at other entries once the lines change.
+        self.clear_marks();
+
+        self.update_rows();
     }
 
     pub fn lines(&self) -> Vec<Line> {
@@ -129,22 +159,53 @@ impl Window {
         self.screen.refresh();
     }
 
+    // Draws the lines right away, for components
+    // reading input in between their renders.
+    pub fn redraw(&mut self) {
+        self.queue_update();
+        self.refresh();
+    }
+
     fn queue_update(&mut self) {
-        let lines  = self.lines.len();
+        let rows   = self.rows.len();
         let height = self.screen.height() as usize;
 
-        let start = self.screen_start;
+        let start = self.screen_start.min(rows);
         let end   = height + self.screen_start;
-        let end   = if end < lines { end } else { lines };
+        let end   = if end < rows { end } else { rows };
+
+        let mut data: Vec<Line> = self.rows[start..end]
+            .iter()
+            .map(|i| {
+                let line = &self.lines[*i];
+                let line = if self.is_marked(*i) { line.marked() } else { line.clone() };
+
+                let value = line.value();
+                let mut ranges = vec![];
+
+                if let Some(pattern) = &self.search {
+                    ranges.extend(pattern.find_ranges(&value));
+                }
+                if let Some(Filter::Pattern(pattern)) = &self.filter {
+                    ranges.extend(pattern.find_ranges(&value));
+                }
 
-        let data  = self.lines[start..end].to_vec();
+                line.highlighted(&ranges)
+            })
+            .collect();
+
+        if let Some(status) = self.status().filter(|_| height > 1) {
+            data.truncate(height - 1);
+            data.resize(height - 1, Line::empty());
+            data.push(status);
+        }
 
         self.screen.set_data(data);
         self.screen.queue_update();
     }
 
     // TODO: think about listening for input outside of rendering methods
-    fn listen_input(&self) -> i32 {
+    pub fn listen_input(&self) -> i32 {
         self.screen.listen_input()
     }
 
@@ -172,6 +233,121 @@ impl Window {
         self.screen.get_cursor_line()
     }
 
+    // Index of the line under the cursor in the lines vector,
+    // past the end of it if the cursor is below the shown lines.
+    pub fn cursor_index(&self) -> usize {
+        let row = self.cursor_row();
+
+        self.rows
+            .get(row)
+            .copied()
+            .unwrap_or_else(|| self.lines.len() + row - self.rows.len())
+    }
+
+    // Moves the cursor to the line at the index, unless it is filtered out.
+    pub fn move_cursor_to(&mut self, index: usize) {
+        if let Some(row) = self.row_of(index) {
+            self.move_cursor_to_row(row);
+        }
+    }
+
+    fn cursor_row(&self) -> usize {
+        self.screen_start + self.cursor_position.y as usize
+    }
+
+    fn move_cursor_to_row(&mut self, row: usize) {
+        if row == self.cursor_row() { return }
+
+        self.set_cursor(Position { x: 0, y: row as i32 });
+    }
+
+    // Row the line at the index is shown on, None if it is filtered out.
+    fn row_of(&self, index: usize) -> Option<usize> {
+        match self.filter {
+            Some(_) => self.rows.iter().position(|i| *i == index),
+            None => Some(index)
+        }
+    }
+
+    pub fn toggle_mark(&mut self, index: usize) {
+        if !self.marks.remove(&index) {
+            self.marks.insert(index);
+        }
+    }
+
+    // Marks the shown lines in the inclusive range, or
+    // unmarks them if all of them are already marked.
+    pub fn toggle_mark_range(&mut self, start: usize, end: usize) {
+        let shown: Vec<usize> = (start..=end)
+            .filter(|i| self.row_of(*i).is_some())
+            .collect();
+
+        if shown.iter().all(|i| self.marks.contains(i)) {
+            for i in shown {
+                self.marks.remove(&i);
+            }
+        } else {
+            self.marks.extend(shown);
+        }
+    }
+
+    // Starts the visual range at the cursor, or marks
+    // the lines between its start and the cursor.
+    pub fn toggle_visual(&mut self) {
+        match self.visual_start.take() {
+            Some(start) => {
+                let visual = self.visual_lines(start);
+                self.marks.extend(visual);
+            },
+            None => self.visual_start = Some(self.cursor_index())
+        }
+    }
+
+    pub fn clear_marks(&mut self) {
+        self.marks.clear();
+        self.visual_start = None;
+    }
+
+    pub fn is_marked(&self, index: usize) -> bool {
+        if self.marks.contains(&index) { return true }
+
+        match (self.visual_start.and_then(|s| self.visual_range(s)), self.row_of(index)) {
+            (Some((start, end)), Some(row)) => start <= row && row <= end,
+            _ => false
+        }
+    }
+
+    // Sorted indices of the marked lines, including
+    // the visual range that is still being selected.
+    pub fn marked(&self) -> Vec<usize> {
+        let mut marked = self.marks.clone();
+
+        if let Some(start) = self.visual_start {
+            marked.extend(self.visual_lines(start));
+        }
+
+        marked
+            .into_iter()
+            .filter(|i| *i < self.lines.len())
+            .collect()
+    }
+
+    // Inclusive range of the rows between the start of the visual
+    // range and the cursor, None if the start is filtered out.
+    fn visual_range(&self, start: usize) -> Option<(usize, usize)> {
+        let start  = self.row_of(start)?;
+        let cursor = self.cursor_row().min(self.rows.len().saturating_sub(1));
+
+        Some((start.min(cursor), start.max(cursor)))
+    }
+
+    fn visual_lines(&self, start: usize) -> Vec<usize> {
+        match self.visual_range(start) {
+            Some((start, end)) => self.rows.iter().copied().skip(start).take(end + 1 - start).collect(),
+            None => vec![]
+        }
+    }
+
     fn set_cursor(&mut self, position: Position) {
         let current_position = self.cursor_position.y + self.screen_start as i32;
 
@@ -196,13 +372,20 @@ impl Window {
         }
     }
 
-    fn move_cursor_down(&mut self) {
-        let delta = self.try_move_cursor_down();
+    pub fn move_cursor_down(&mut self) {
+        let mut delta = self.try_move_cursor_down();
+
+        // The cursor stays above the status line, the lines are scrolled instead.
+        let bottom = self.lines_height() as i32 - 1;
+        while self.cursor_position.y > bottom.max(0) {
+            self.try_move_cursor_up();
+            delta += 1;
+        }
 
         let next_position = self.screen_start + delta as usize;
-        let next_end = next_position + self.height() as usize;
+        let next_end = next_position + self.lines_height();
 
-        if delta > 0 && next_end < self.lines.len() {
+        if delta > 0 && next_end <= self.rows.len() {
             self.move_screen_down(delta as usize);
         }
     }
@@ -217,7 +400,7 @@ impl Window {
     }
 
     pub fn move_screen_down(&mut self, delta: usize) {
-        if self.screen_start + delta >= self.lines.len() { return }
+        if self.screen_start + delta >= self.rows.len() { return }
 
         self.screen_start += delta;
         self.queue_update();
@@ -230,42 +413,149 @@ impl Window {
         self.queue_update();
     }
 
-    pub fn move_next(&mut self, term: &str) {
-        if term.is_empty() { return }
+    pub fn set_search(&mut self, pattern: Option<SearchPattern>) {
+        self.search = pattern;
+        self.search_wrapped = false;
 
-        let start = self.cursor_position.y as usize + self.screen_start + 1;
+        self.scroll_above_status();
+    }
 
-        let next = self.lines
-            .iter()
-            .skip(start)
-            .map(|l| l.value())
-            .position(|l| l.contains(term));
+    pub fn search_pattern(&self) -> Option<&SearchPattern> {
+        self.search.as_ref()
+    }
+
+    // Moves to the next shown line matching the search,
+    // wrapping around to the top once the bottom is reached.
+    pub fn move_next(&mut self) {
+        let current = self.cursor_row();
+        let count = self.rows.len();
+
+        let next = (1..=count)
+            .map(|i| (current + i) % count)
+            .find(|r| self.is_search_match(self.rows[*r]));
 
-        if let Some(position) = next {
-            self.set_cursor(
-                Position { x: 0, y: (start + position) as i32 }
-            );
+        if let Some(row) = next {
+            self.search_wrapped = row <= current;
+            self.move_to_match(row);
         }
     }
 
-    pub fn move_prev(&mut self, term: &str) {
-        if term.is_empty() { return }
+    // Moves to the previous shown line matching the search,
+    // wrapping around to the bottom once the top is reached.
+    pub fn move_prev(&mut self) {
+        let current = self.cursor_row();
+        let count = self.rows.len();
 
-        let end = self.cursor_position.y as usize;
+        let prev = (1..=count)
+            .map(|i| (current + count - i) % count)
+            .find(|r| self.is_search_match(self.rows[*r]));
 
-        let prev = self.lines
-            .iter()
-            .take(self.screen_start + end)
-            .map(|l| l.value())
-            .rposition(|l| l.contains(term));
+        if let Some(row) = prev {
+            self.search_wrapped = row >= current;
+            self.move_to_match(row);
+        }
+    }
+
+    fn is_search_match(&self, index: usize) -> bool {
+        self.search
+            .as_ref()
+            .is_some_and(|p| p.is_match(&self.lines[index].value()))
+    }
+
+    fn move_to_match(&mut self, row: usize) {
+        self.move_cursor_to_row(row);
+    }
+
+    // Rows of the screen the lines are shown on, all but
+    // the last one while it is taken by the status line.
+    fn lines_height(&self) -> usize {
+        let height = self.height().max(0) as usize;
+        let status = self.search.is_some() || self.filter.is_some();
+
+        if status && height > 1 { height - 1 } else { height }
+    }
+
+    // Scrolls the line under the cursor out from under the status line.
+    fn scroll_above_status(&mut self) {
+        let bottom = self.lines_height() as i32 - 1;
 
-        if let Some(position) = prev {
-            self.set_cursor(
-                Position { x: 0, y: position as i32 }
-            );
+        while self.cursor_position.y > bottom.max(0) {
+            self.screen_start += 1;
+            self.try_move_cursor_up();
         }
     }
 
+    // Shows only the lines passing the filter starting from the
+    // top, or all of them again keeping the cursor on its line.
+    pub fn set_filter(&mut self, filter: Option<Filter>) {
+        let index = self.cursor_index();
+        let filtering = filter.is_some();
+
+        self.filter = filter;
+        self.update_rows();
+        self.scroll_above_status();
+
+        if filtering {
+            self.move_cursor_to_row(0);
+        } else {
+            self.move_cursor_to(index);
+        }
+    }
+
+    pub fn filter(&self) -> Option<&Filter> {
+        self.filter.as_ref()
+    }
+
+    fn update_rows(&mut self) {
+        self.rows = match &self.filter {
+            Some(filter) => {
+                let keys: Vec<String> = self.lines.iter().map(Line::filter_key).collect();
+                filter.apply(&keys)
+            },
+            None => (0..self.lines.len()).collect()
+        };
+    }
+
+    // The filter with the number of lines passing it, followed by the search,
+    // its match count and the position of the cursor among the matches.
+    fn status(&self) -> Option<Line> {
+        let mut parts = vec![];
+
+        if let Some(filter) = &self.filter {
+            parts.push(Part::new(&format!(" filter: {} ", filter.term()), Some(vec![Style::Bold, Style::Match])));
+            parts.push(Part::plain(&format!(" {}/{} lines ", self.rows.len(), self.lines.len())));
+        }
+
+        if let Some(search) = self.search_status() {
+            parts.extend(search);
+        }
+
+        if parts.is_empty() { None } else { Some(Line::new(parts)) }
+    }
+
+    fn search_status(&self) -> Option<Vec<Part>> {
+        let pattern = self.search.as_ref()?;
+
+        let matches: Vec<usize> = self.rows
+            .iter()
+            .copied()
+            .filter(|i| self.is_search_match(*i))
+            .collect();
+
+        let position = match matches.iter().position(|i| *i == self.cursor_index()) {
+            Some(position) => format!("match {}/{}", position + 1, matches.len()),
+            None if matches.is_empty() => "no matches".to_owned(),
+            None => format!("{} matches", matches.len())
+        };
+
+        let wrapped = if self.search_wrapped { ", search wrapped" } else { "" };
+
+        Some(vec![
+            Part::new(&format!(" /{} ", pattern.term), Some(vec![Style::Bold, Style::Match])),
+            Part::plain(&format!(" {}{}", position, wrapped))
+        ])
+    }
+
     fn try_move_cursor_down(&mut self) -> i32 {
         let new_position = Position {
             x: self.cursor_position.x,
@@ -335,6 +625,10 @@ pub enum Style {
     Underlined,
     Bold,
     Painted(RGB, RGB),
+    // Applied by the window to the parts of marked lines.
+    Marked,
+    // Applied by the window to the text matching the search.
+    Match,
     Plain
 }
 
@@ -372,12 +666,19 @@ impl Part {
 
 #[derive(Clone)]
 pub struct Line {
-    pub parts: Vec<Part>
+    pub parts: Vec<Part>,
+    // Text the line is matched against when filtering, its value if none.
+    pub key: Option<String>
 }
 
 impl Line {
     pub fn new(parts: Vec<Part>) -> Line {
-        Line { parts }
+        Line { parts, key: None }
+    }
+
+    pub fn with_key(mut self, key: &str) -> Line {
+        self.key = Some(key.to_owned());
+        self
     }
 
     pub fn plain(value: &str) -> Line {
@@ -402,12 +703,65 @@ impl Line {
         Line::from_string(from.to_owned(), styles)
     }
 
+    pub fn marked(&self) -> Line {
+        let parts = self.parts
+            .iter()
+            .map(|p| {
+                let mut styles = p.styles.clone();
+                styles.push(Style::Marked);
+
+                Part { value: p.value.clone(), styles }
+            })
+            .collect();
+
+        Line { parts, key: self.key.clone() }
+    }
+
+    // Splits the parts at the byte ranges of the value,
+    // adding the match style to the text inside them.
+    pub fn highlighted(&self, ranges: &[(usize, usize)]) -> Line {
+        if ranges.is_empty() { return self.clone() }
+
+        let mut parts = vec![];
+        let mut offset = 0;
+
+        for part in &self.parts {
+            let end = offset + part.value.len();
+
+            // Boundaries of the ranges within the part, relative to it.
+            let mut cuts = vec![0, part.value.len()];
+            for (start, stop) in ranges {
+                cuts.extend([*start, *stop].iter().filter(|c| **c > offset && **c < end).map(|c| c - offset));
+            }
+            cuts.sort_unstable();
+            cuts.dedup();
+
+            for cut in cuts.windows(2) {
+                let mut styles = part.styles.clone();
+
+                if ranges.iter().any(|(start, stop)| offset + cut[0] >= *start && offset + cut[0] < *stop) {
+                    styles.push(Style::Match);
+                }
+
+                parts.push(Part { value: part.value[cut[0]..cut[1]].to_owned(), styles });
+            }
+
+            offset = end;
+        }
+
+        Line { parts, key: self.key.clone() }
+    }
+
     pub fn value(&self) -> String {
         self.parts
             .iter()
             .map(|p| p.value.clone())
             .fold(String::new(), |agg, val| agg + &val)
     }
+
+    pub fn filter_key(&self) -> String {
+        self.key.clone().unwrap_or_else(|| self.value())
+    }
 }
 
 #[derive(Clone, Copy, Default, PartialEq)]
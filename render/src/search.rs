@@ -0,0 +1,140 @@
+use std::cmp::Reverse;
+
+use regex::{Regex, RegexBuilder};
+
+#[derive(Clone, Copy)]
+pub struct SearchFlags {
+    // Treat the term as a regular expression instead of literal text.
+    pub regex: bool,
+    // Ignore case unless the term has an upper case letter in it.
+    pub smart_case: bool,
+    // Only match the term as a whole word.
+    pub whole_word: bool
+}
+
+impl Default for SearchFlags {
+    fn default() -> SearchFlags {
+        SearchFlags { regex: false, smart_case: true, whole_word: false }
+    }
+}
+
+// A search term compiled along with its flags, the windows match lines against.
+#[derive(Clone)]
+pub struct SearchPattern {
+    pub term: String,
+    pub flags: SearchFlags,
+    regex: Regex
+}
+
+impl SearchPattern {
+    // Fails with a one line description of the error if the term is not a valid regex.
+    pub fn new(term: &str, flags: SearchFlags) -> Result<SearchPattern, String> {
+        let mut pattern = if flags.regex { term.to_owned() } else { regex::escape(term) };
+
+        if flags.whole_word {
+            pattern = format!(r"\b(?:{})\b", pattern);
+        }
+
+        let ignore_case = flags.smart_case && !term.chars().any(char::is_uppercase);
+
+        RegexBuilder::new(&pattern)
+            .case_insensitive(ignore_case)
+            .build()
+            .map(|regex| SearchPattern { term: term.to_owned(), flags, regex })
+            .map_err(|e| {
+                // The full error also repeats the pattern pointing out where it failed.
+                e.to_string()
+                    .lines()
+                    .last()
+                    .unwrap_or_default()
+                    .trim()
+                    .to_owned()
+            })
+    }
+
+    pub fn is_match(&self, line: &str) -> bool {
+        self.regex.is_match(line)
+    }
+
+    // Byte ranges of the matches in the line, leaving out empty ones.
+    pub fn find_ranges(&self, line: &str) -> Vec<(usize, usize)> {
+        self.regex
+            .find_iter(line)
+            .filter(|m| !m.as_str().is_empty())
+            .map(|m| (m.start(), m.end()))
+            .collect()
+    }
+}
+
+// What the lines of a window are narrowed down to, either the lines
+// matching a search or the ones fuzzy matching a query, best first.
+#[derive(Clone)]
+pub enum Filter {
+    Pattern(SearchPattern),
+    Fuzzy(String)
+}
+
+impl Filter {
+    pub fn term(&self) -> &str {
+        match self {
+            Filter::Pattern(pattern) => &pattern.term,
+            Filter::Fuzzy(query) => query
+        }
+    }
+
+    // Indices of the texts passing the filter, in the order to show them in.
+    pub fn apply(&self, texts: &[String]) -> Vec<usize> {
+        match self {
+            Filter::Pattern(pattern) => {
+                (0..texts.len())
+                    .filter(|i| pattern.is_match(&texts[*i]))
+                    .collect()
+            },
+            Filter::Fuzzy(query) => {
+                let mut scored: Vec<(i64, usize)> = texts
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, text)| fuzzy_score(query, text).map(|score| (score, i)))
+                    .collect();
+
+                // Shorter texts first among equally good matches, the
+                // sort being stable keeps the original order otherwise.
+                scored.sort_by_key(|(score, i)| (Reverse(*score), texts[*i].len()));
+                scored.into_iter().map(|(_, i)| i).collect()
+            }
+        }
+    }
+}
+
+// Scores how well the text matches the characters of the query appearing in it
+// in order, higher for consecutive ones and ones starting words. None if they don't.
+fn fuzzy_score(query: &str, text: &str) -> Option<i64> {
+    let ignore_case = !query.chars().any(char::is_uppercase);
+    let normalize = |c: char| if ignore_case { c.to_ascii_lowercase() } else { c };
+
+    let text: Vec<char> = text.chars().collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous: Option<usize> = None;
+
+    for q in query.chars().map(normalize) {
+        let found = position + text[position..].iter().position(|c| normalize(*c) == q)?;
+
+        score += 1;
+
+        match previous {
+            Some(p) if p + 1 == found => score += 8,
+            Some(p) => score -= (found - p - 1).min(5) as i64,
+            None => score -= found.min(10) as i64
+        }
+
+        if found == 0 || matches!(text[found - 1], ' ' | '/' | '-' | '_' | '.') {
+            score += 6;
+        }
+
+        previous = Some(found);
+        position = found + 1;
+    }
+
+    Some(score)
+}
@@ -0,0 +1,254 @@
+// Parsing and resolving of the conflict markers git leaves
+// in files it could not merge automatically.
+
+const OURS_MARKER: &str = "<<<<<<<";
+const BASE_MARKER: &str = "|||||||";
+const SEPARATOR_MARKER: &str = "=======";
+const THEIRS_MARKER: &str = ">>>>>>>";
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Resolution {
+    Ours,
+    Theirs,
+    Both
+}
+
+pub struct Conflict {
+    // Marker lines are kept as they were, so unresolved
+    // conflicts can be written back unchanged.
+    pub ours_marker: String,
+    pub ours: Vec<String>,
+    pub base_marker: Option<String>,
+    pub base: Vec<String>,
+    pub separator: String,
+    pub theirs: Vec<String>,
+    pub theirs_marker: String,
+    pub resolution: Option<Resolution>
+}
+
+impl Conflict {
+    pub fn resolved_lines(&self) -> Vec<String> {
+        match self.resolution {
+            Some(Resolution::Ours) => self.ours.clone(),
+            Some(Resolution::Theirs) => self.theirs.clone(),
+            Some(Resolution::Both) => [self.ours.as_slice(), self.theirs.as_slice()].concat(),
+            None => {
+                let mut lines = vec![self.ours_marker.clone()];
+                lines.extend(self.ours.iter().cloned());
+
+                if let Some(base_marker) = &self.base_marker {
+                    lines.push(base_marker.clone());
+                    lines.extend(self.base.iter().cloned());
+                }
+
+                lines.push(self.separator.clone());
+                lines.extend(self.theirs.iter().cloned());
+                lines.push(self.theirs_marker.clone());
+
+                lines
+            }
+        }
+    }
+}
+
+pub enum Hunk {
+    Text(Vec<String>),
+    Conflict(Conflict)
+}
+
+enum Section {
+    Text,
+    Ours,
+    Base,
+    Theirs
+}
+
+// Splits the file content into plain text and conflict hunks. Lines keep
+// their line endings so that writing the file back does not change them.
+// A conflict left unterminated at the end of the file is treated as text.
+pub fn parse(content: &str) -> Vec<Hunk> {
+    let mut hunks: Vec<Hunk> = vec![];
+    let mut text: Vec<String> = vec![];
+    let mut pending: Vec<String> = vec![];
+    let mut conflict: Option<Conflict> = None;
+    let mut section = Section::Text;
+
+    for line in content.split_inclusive('\n') {
+        match section {
+            Section::Text => {
+                if is_marker(line, OURS_MARKER) {
+                    if !text.is_empty() {
+                        hunks.push(Hunk::Text(std::mem::take(&mut text)));
+                    }
+
+                    pending.push(line.to_owned());
+                    conflict = Some(Conflict {
+                        ours_marker: line.to_owned(),
+                        ours: vec![],
+                        base_marker: None,
+                        base: vec![],
+                        separator: "".to_owned(),
+                        theirs: vec![],
+                        theirs_marker: "".to_owned(),
+                        resolution: None
+                    });
+                    section = Section::Ours;
+                } else {
+                    text.push(line.to_owned());
+                }
+            },
+            Section::Ours | Section::Base | Section::Theirs => {
+                pending.push(line.to_owned());
+                let current = conflict.as_mut().unwrap();
+
+                match section {
+                    Section::Ours if is_marker(line, BASE_MARKER) => {
+                        current.base_marker = Some(line.to_owned());
+                        section = Section::Base;
+                    },
+                    Section::Ours | Section::Base if is_marker(line, SEPARATOR_MARKER) => {
+                        current.separator = line.to_owned();
+                        section = Section::Theirs;
+                    },
+                    Section::Theirs if is_marker(line, THEIRS_MARKER) => {
+                        current.theirs_marker = line.to_owned();
+                        hunks.push(Hunk::Conflict(conflict.take().unwrap()));
+                        pending.clear();
+                        section = Section::Text;
+                    },
+                    Section::Ours => current.ours.push(line.to_owned()),
+                    Section::Base => current.base.push(line.to_owned()),
+                    _ => current.theirs.push(line.to_owned())
+                }
+            }
+        }
+    }
+
+    text.append(&mut pending);
+
+    if !text.is_empty() {
+        hunks.push(Hunk::Text(text));
+    }
+
+    hunks
+}
+
+// Whether the line is exactly the marker, followed by a space and a label for all
+// but the separator. Longer runs, e.g. "========" underlining a heading, are text.
+fn is_marker(line: &str, marker: &str) -> bool {
+    let rest = match line.trim_end_matches(&['\r', '\n'][..]).strip_prefix(marker) {
+        Some(rest) => rest,
+        None => return false
+    };
+
+    rest.is_empty() || (marker != SEPARATOR_MARKER && rest.starts_with(' '))
+}
+
+pub fn conflict_count(hunks: &[Hunk]) -> usize {
+    hunks
+        .iter()
+        .filter(|h| matches!(h, Hunk::Conflict(_)))
+        .count()
+}
+
+pub fn unresolved_count(hunks: &[Hunk]) -> usize {
+    hunks
+        .iter()
+        .filter(|h| matches!(h, Hunk::Conflict(c) if c.resolution.is_none()))
+        .count()
+}
+
+// Puts the file back together, replacing every resolved conflict with the
+// chosen side(s). Unresolved conflicts keep their markers.
+pub fn resolve(hunks: &[Hunk]) -> String {
+    hunks
+        .iter()
+        .flat_map(|h| {
+            match h {
+                Hunk::Text(lines) => lines.clone(),
+                Hunk::Conflict(conflict) => conflict.resolved_lines()
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conflicts(hunks: &[Hunk]) -> Vec<&Conflict> {
+        hunks
+            .iter()
+            .filter_map(|h| match h {
+                Hunk::Conflict(conflict) => Some(conflict),
+                Hunk::Text(_) => None
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parses_a_conflict() {
+        let content = "before\n<<<<<<< HEAD\nours\n=======\ntheirs\n>>>>>>> feature\nafter\n";
+        let hunks = parse(content);
+
+        assert_eq!(hunks.len(), 3);
+        assert_eq!(conflict_count(&hunks), 1);
+
+        let conflict = conflicts(&hunks)[0];
+        assert_eq!(conflict.ours, vec!["ours\n"]);
+        assert_eq!(conflict.theirs, vec!["theirs\n"]);
+        assert!(conflict.base_marker.is_none());
+
+        // Unresolved conflicts are written back unchanged.
+        assert_eq!(resolve(&hunks), content);
+    }
+
+    #[test]
+    fn parses_a_diff3_base_section() {
+        let content = "<<<<<<< HEAD\r\nours\r\n||||||| base\r\nbase\r\n=======\r\ntheirs\r\n>>>>>>> feature\r\n";
+        let mut hunks = parse(content);
+
+        assert_eq!(hunks.len(), 1);
+
+        if let Hunk::Conflict(conflict) = &mut hunks[0] {
+            assert_eq!(conflict.base_marker.as_deref(), Some("||||||| base\r\n"));
+            assert_eq!(conflict.base, vec!["base\r\n"]);
+            assert_eq!(conflict.theirs, vec!["theirs\r\n"]);
+
+            conflict.resolution = Some(Resolution::Both);
+        }
+
+        assert_eq!(resolve(&hunks), "ours\r\ntheirs\r\n");
+    }
+
+    #[test]
+    fn keeps_heading_underlines_as_text() {
+        let content = concat!(
+            "Title\n",
+            "========\n",
+            "<<<<<<< HEAD\n",
+            "Ours\n",
+            "========\n",
+            "=======\n",
+            "Theirs\n",
+            ">>>>>>>>\n",
+            ">>>>>>> feature\n",
+            "<<<<<<<<\n"
+        );
+        let mut hunks = parse(content);
+
+        assert_eq!(conflict_count(&hunks), 1);
+
+        let conflict = conflicts(&hunks)[0];
+        assert_eq!(conflict.ours, vec!["Ours\n", "========\n"]);
+        assert_eq!(conflict.theirs, vec!["Theirs\n", ">>>>>>>>\n"]);
+
+        for hunk in hunks.iter_mut() {
+            if let Hunk::Conflict(conflict) = hunk {
+                conflict.resolution = Some(Resolution::Theirs);
+            }
+        }
+
+        assert_eq!(resolve(&hunks), "Title\n========\nTheirs\n>>>>>>>>\n<<<<<<<<\n");
+    }
+}
@@ -1,11 +1,14 @@
 #![allow(dead_code)]
+use std::collections::HashMap;
+
 pub enum FileState {
     Unknown,
     Modified,
     Deleted,
     Added,
     Staged,
-    Untracked
+    Untracked,
+    Conflicted
 }
 
 fn parse_status(path: &str) -> (char, char) {
@@ -35,12 +38,24 @@ pub fn is_file_modified(path: &str) -> bool {
 
 }
 
+// Unmerged paths are DD, AU, UD, UA, DU, AA and UU.
+pub fn is_conflicted(path: &str) -> bool {
+    let (first, second) = parse_status(path);
+
+    first == 'U'
+        || second == 'U'
+        || (first == 'A' && second == 'A')
+        || (first == 'D' && second == 'D')
+}
+
 pub fn parse_file_state(path: &str) -> FileState {
     // https://git-scm.com/docs/git-status
     let state = &path[..3];
     let (first, second) = parse_status(path);
 
-    if second == 'M' || first == 'A' {
+    if is_conflicted(path) {
+        FileState::Conflicted
+    } else if second == 'M' || first == 'A' {
         FileState::Modified
     } else if first == 'M' || first == 'A' {
         FileState::Staged
@@ -60,13 +75,6 @@ pub fn current_branch() -> String {
         .clone()
 }
 
-pub fn last_origin_commit_hash() -> String {
-    run(vec!["rev-parse", &format!("origin/{}", &current_branch())])
-        .first()
-        .unwrap()
-        .clone()
-}
-
 pub fn last_commit_hash() -> String {
     run(vec!["rev-parse", &current_branch()])
         .first()
@@ -74,26 +82,53 @@ pub fn last_commit_hash() -> String {
         .clone()
 }
 
-pub fn last_origin_commit() -> String {
-    run(vec!["log", "-1", "--oneline", "--no-decorate", &format!("origin/{}", &current_branch())])
+pub fn last_commit() -> String {
+    run(vec!["log", "-1", "--oneline", "--no-decorate"])
         .first()
         .unwrap()
         .clone()
 }
 
-pub fn last_commit() -> String {
-    run(vec!["log", "-1", "--oneline", "--no-decorate"])
+pub fn is_detached_head() -> bool {
+    try_run(vec!["symbolic-ref", "-q", "HEAD"]).is_none()
+}
+
+// Returns the configured upstream of the current branch, e.g. origin/master.
+pub fn upstream() -> Option<String> {
+    try_run(vec!["rev-parse", "--abbrev-ref", "--symbolic-full-name", "@{u}"])?
         .first()
-        .unwrap()
-        .clone()
+        .cloned()
 }
 
-pub fn origin_head_branch() -> String {
-    run(vec!["show", "-s", "--pretty=%d", &format!("origin/{}", &current_branch())])
+pub fn last_upstream_commit(upstream: &str) -> String {
+    run(vec!["log", "-1", "--oneline", "--no-decorate", upstream])
         .first()
         .unwrap()
         .clone()
+}
+
+// Returns how many commits HEAD is (ahead, behind) the upstream.
+pub fn ahead_behind(upstream: &str) -> (usize, usize) {
+    let range = format!("HEAD...{}", upstream);
+    let counts = try_run(vec!["rev-list", "--left-right", "--count", &range])
+        .and_then(|output| output.first().cloned())
+        .unwrap_or_default();
+
+    let mut counts = counts
+        .split_whitespace()
+        .map(|c| c.parse::<usize>().unwrap_or(0));
+
+    (counts.next().unwrap_or(0), counts.next().unwrap_or(0))
+}
+
+pub fn unpushed_commits(upstream: &str) -> Vec<String> {
+    let range = format!("{}..HEAD", upstream);
+    try_run(vec!["log", "--oneline", "--no-decorate", &range]).unwrap_or_default()
+}
 
+pub fn unpulled_commits(upstream: &str) -> Vec<String> {
+    let range = format!("HEAD..{}", upstream);
+    try_run(vec!["log", "--oneline", "--no-decorate", &range]).unwrap_or_default()
 }
 
 pub fn head_branch() -> String {
@@ -103,37 +138,215 @@ pub fn head_branch() -> String {
         .clone()
 }
 
+// Untracked directories are listed file by file, ignored files are left out.
 pub fn status() -> Vec<String> {
-    run(vec!["status", "-s"])
+    run(vec!["status", "-s", "-uall"])
 }
 
 pub fn diff_file(path: &str) -> Vec<String> {
     run(vec!["--no-pager", "diff", path])
 }
 
-pub fn diff_commit(commit_hash: &str) -> Vec<String> {
-    run(vec!["--no-pager", "diff", &(commit_hash.to_owned() + "^!")])
+// Contents of the file as of the revision, None if it did not exist then.
+// The path is relative to the repository root, like the paths in commits.
+pub fn file_at_revision(revision: &str, path: &str) -> Option<Vec<String>> {
+    let object = format!("{}:{}", revision, path);
+    try_run(vec!["--no-pager", "show", &object])
+}
+
+// Changes in the working tree to the file since the revision.
+// The path is relative to the repository root, as for file_at_revision.
+pub fn diff_worktree(revision: &str, path: &str) -> Vec<String> {
+    let pathspec = format!(":(top){}", path);
+    run(vec!["--no-pager", "diff", revision, "--", &pathspec])
+}
+
+// Turns a path relative to the current directory into one relative to
+// the repository root, e.g. "../README.md" in "src" into "README.md".
+pub fn root_relative_path(path: &str) -> String {
+    let prefix = try_run(vec!["rev-parse", "--show-prefix"])
+        .and_then(|output| output.first().cloned())
+        .unwrap_or_default();
+
+    let mut parts: Vec<&str> = Vec::new();
+    for part in prefix.split('/').chain(path.split('/')) {
+        match part {
+            "" | "." => {}
+            ".." => { parts.pop(); }
+            _ => parts.push(part)
+        }
+    }
+
+    parts.join("/")
+}
+
+// Diff of the commit against the parent, or everything the commit added
+// if it has no parent, optionally limited to the paths. A renamed file
+// shows up as a rename if both of its paths are given.
+pub fn diff_commit(commit_hash: &str, parent: Option<&str>, paths: &[&str]) -> Vec<String> {
+    let mut args = match parent {
+        Some(parent) => vec!["--no-pager", "diff", "--find-renames", parent, commit_hash],
+        None => vec!["--no-pager", "show", "--find-renames", "--format=", commit_hash]
+    };
+
+    if !paths.is_empty() {
+        args.push("--");
+        args.extend(paths);
+    }
+
+    run(args)
 }
 
-pub fn add_file(path: &str) {
-    run(vec!["add", path]);
+pub struct CommitDetails {
+    pub hash: String,
+    pub parents: Vec<String>,
+    pub author: String,
+    pub author_date: String,
+    pub committer: String,
+    pub committer_date: String,
+    // One of the %G? letters, N for an unsigned commit.
+    pub signature: char,
+    pub signer: String,
+    pub trailers: Vec<String>,
+    pub message: Vec<String>
 }
 
-pub fn unstage_file(path: &str) {
-    run(vec!["reset", path]);
+impl CommitDetails {
+    pub fn signature_description(&self) -> &'static str {
+        match self.signature {
+            'G' => "good",
+            'B' => "bad",
+            'U' => "good, unknown validity",
+            'X' => "good, expired",
+            'Y' => "good, made by an expired key",
+            'R' => "good, made by a revoked key",
+            'E' => "cannot be checked",
+            _ => "none"
+        }
+    }
+}
+
+pub fn commit_details(commit_hash: &str) -> Option<CommitDetails> {
+    let format = "--format=%H%x00%P%x00%an <%ae>%x00%ad%x00%cn <%ce>%x00%cd%x00%G?%x00%GS%x00%(trailers:only,unfold)%x00%B";
+
+    let output = try_run(vec!["show", "--no-patch", "--date=iso", format, commit_hash])?.join("\n");
+    let fields: Vec<&str> = output.split('\0').collect();
+    if fields.len() < 10 { return None }
+
+    let lines = |field: &str| -> Vec<String> {
+        field.trim_end().lines().map(str::to_owned).collect()
+    };
+
+    Some(CommitDetails {
+        hash: fields[0].to_owned(),
+        parents: fields[1].split_whitespace().map(str::to_owned).collect(),
+        author: fields[2].to_owned(),
+        author_date: fields[3].to_owned(),
+        committer: fields[4].to_owned(),
+        committer_date: fields[5].to_owned(),
+        signature: fields[6].chars().next().unwrap_or('N'),
+        signer: fields[7].to_owned(),
+        trailers: lines(fields[8]),
+        message: lines(fields[9])
+    })
 }
 
-pub fn push(push_args: Option<Vec<&str>>) -> Vec<String> {
+pub struct FileStat {
+    pub path: String,
+    // Binary files have no line counts.
+    pub added: Option<usize>,
+    pub deleted: Option<usize>
+}
+
+// Changed files of the commit compared to the parent, see diff_commit.
+pub fn commit_file_stats(commit_hash: &str, parent: Option<&str>) -> Vec<FileStat> {
+    let args = match parent {
+        Some(parent) => vec!["diff", "--numstat", "--no-renames", parent, commit_hash],
+        None => vec!["show", "--numstat", "--no-renames", "--format=", commit_hash]
+    };
+
+    try_run(args)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '\t');
+
+            let added = fields.next()?.parse().ok();
+            let deleted = fields.next()?.parse().ok();
+
+            Some(FileStat { path: fields.next()?.to_owned(), added, deleted })
+        })
+        .collect()
+}
+
+pub fn add_files(paths: &[&str]) {
+    let mut args = vec!["add", "--"];
+    args.extend(paths);
+
+    run(args);
+}
+
+pub fn add_all() {
+    run(vec!["add", "--all"]);
+}
+
+pub fn unstage_files(paths: &[&str]) {
+    let mut args = vec!["reset", "--"];
+    args.extend(paths);
+
+    run(args);
+}
+
+pub fn remotes() -> Vec<String> {
+    run(vec!["remote"])
+        .into_iter()
+        .filter(|r| !r.is_empty())
+        .collect()
+}
+
+pub fn fetch(fetch_args: Option<Vec<&str>>, remote: Option<&str>) -> Vec<String> {
+    let mut args = vec!["fetch"];
+
+    if let Some(process_args) = fetch_args {
+        args.extend(process_args);
+    }
+
+    if let Some(remote) = remote {
+        args.push(remote);
+    }
+
+    run(args)
+}
+
+pub fn pull(pull_args: Option<Vec<&str>>, remote: Option<&str>, branch: Option<&str>) -> Vec<String> {
+    let mut args = vec!["pull"];
+
+    if let Some(process_args) = pull_args {
+        args.extend(process_args);
+    }
+
+    if let Some(remote) = remote {
+        args.push(remote);
+
+        // A branch only makes sense when pulling from an explicit remote.
+        if let Some(branch) = branch {
+            args.push(branch);
+        }
+    }
+
+    run(args)
+}
+
+pub fn push(push_args: Option<Vec<&str>>, remote: &str, refspec: Option<&str>) -> Vec<String> {
     let mut args = vec!["push"];
 
     if let Some(process_args) = push_args {
         args.extend(process_args);
     }
 
-    // TODO: what if it's not origin?
-    // TODO: what if I want to choose branch?
     let current_branch = current_branch();
-    args.extend(vec!["origin", &current_branch]);
+    args.push(remote);
+    args.push(refspec.unwrap_or(&current_branch));
 
     run(args)
 }
@@ -145,44 +358,595 @@ pub fn commit(commit_args: Option<Vec<&str>>) -> Vec<String> {
         args.extend(process_args);
     }
 
-    let output = std::process::Command::new("git")
-        .args(args)
-        .spawn()
-        .unwrap()
-        .wait_with_output()
-        .expect("failed to execute process");
+    run_interactive(args, vec![])
+}
 
-    output_lines(output)
+pub fn stash_list() -> Vec<String> {
+    try_run(vec!["stash", "list"]).unwrap_or_default()
+}
+
+pub fn stash_push(stash_args: Option<Vec<&str>>, message: Option<&str>, paths: Option<Vec<&str>>) -> Vec<String> {
+    let mut args = vec!["stash", "push"];
+
+    if let Some(process_args) = stash_args {
+        args.extend(process_args);
+    }
+
+    if let Some(message) = message {
+        args.extend(vec!["--message", message]);
+    }
+
+    if let Some(paths) = paths {
+        args.push("--");
+        args.extend(paths);
+    }
+
+    run(args)
+}
+
+pub fn stash_apply(stash: &str) -> Vec<String> {
+    run(vec!["stash", "apply", stash])
+}
+
+pub fn stash_pop(stash: &str) -> Vec<String> {
+    run(vec!["stash", "pop", stash])
+}
+
+pub fn stash_drop(stash: &str) -> Vec<String> {
+    run(vec!["stash", "drop", stash])
+}
+
+pub fn stash_show(stash: &str) -> Vec<String> {
+    run(vec!["--no-pager", "stash", "show", "--patch", stash])
+}
+
+pub struct Branch {
+    pub name: String,
+    pub is_head: bool,
+    pub is_remote: bool,
+    pub upstream: Option<String>,
+    // e.g. "ahead 1, behind 2" or "gone", empty when up to date.
+    pub track: String,
+    pub date: String,
+    // Committer date as unix time.
+    pub timestamp: u64,
+    pub author: String,
+    pub subject: String
 }
 
-pub fn branch() -> Vec<String> {
-    run(vec!["--no-pager", "branch"])
+// Lists local and remote-tracking branches, sort being
+// a for-each-ref sort key, e.g. -committerdate.
+pub fn branches(sort: &str) -> Vec<Branch> {
+    let sort_arg = format!("--sort={}", sort);
+    let format = "--format=%(HEAD)%00%(refname)%00%(refname:short)%00%(upstream:short)%00%(upstream:track,nobracket)%00%(committerdate:relative)%00%(committerdate:unix)%00%(authorname)%00%(contents:subject)";
+
+    try_run(vec!["for-each-ref", &sort_arg, format, "refs/heads", "refs/remotes"])
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split('\0').collect();
+            if fields.len() < 9 { return None }
+
+            // Skip symbolic refs like origin/HEAD.
+            if fields[1].starts_with("refs/remotes/") && fields[1].ends_with("/HEAD") { return None }
+
+            Some(Branch {
+                name: fields[2].to_owned(),
+                is_head: fields[0] == "*",
+                is_remote: fields[1].starts_with("refs/remotes/"),
+                upstream: if fields[3].is_empty() { None } else { Some(fields[3].to_owned()) },
+                track: fields[4].to_owned(),
+                date: fields[5].to_owned(),
+                timestamp: fields[6].parse().unwrap_or(0),
+                author: fields[7].to_owned(),
+                subject: fields[8].to_owned()
+            })
+        })
+        .collect()
+}
+
+pub fn branch_exists(branch_name: &str) -> bool {
+    let branch_ref = format!("refs/heads/{}", branch_name);
+    try_run(vec!["rev-parse", "--verify", "--quiet", &branch_ref]).is_some()
+}
+
+// Local branches whose tips are reachable from the base.
+pub fn merged_branches(base: &str) -> Vec<String> {
+    try_run(vec!["for-each-ref", "--merged", base, "--format=%(refname:short)", "refs/heads"])
+        .unwrap_or_default()
+}
+
+// Local branches with commits the base does not have.
+pub fn unmerged_branches(base: &str) -> Vec<String> {
+    try_run(vec!["for-each-ref", "--no-merged", base, "--format=%(refname:short)", "refs/heads"])
+        .unwrap_or_default()
+}
+
+// Creates a local branch tracking the remote one and checks it out.
+pub fn checkout_remote_branch(remote_branch: &str) -> Vec<String> {
+    run(vec!["checkout", "--track", remote_branch])
+}
+
+// Returns lines of remote name and its fetch url.
+pub fn remotes_verbose() -> Vec<String> {
+    run(vec!["remote", "--verbose"])
+        .into_iter()
+        .filter(|r| r.ends_with("(fetch)"))
+        .map(|r| r.trim_end_matches("(fetch)").trim_end().to_owned())
+        .collect()
+}
+
+pub fn add_remote(name: &str, url: &str) -> Vec<String> {
+    run(vec!["remote", "add", name, url])
+}
+
+pub fn rename_remote(old_name: &str, new_name: &str) -> Vec<String> {
+    run(vec!["remote", "rename", old_name, new_name])
+}
+
+pub fn remove_remote(name: &str) -> Vec<String> {
+    run(vec!["remote", "remove", name])
+}
+
+pub fn prune_remote(name: &str) -> Vec<String> {
+    run(vec!["remote", "prune", name])
 }
 
 pub fn checkout_branch(branch_name: &str) -> Vec<String> {
     run(vec!["checkout", branch_name])
 }
 
-pub fn checkout_file(file_path: &str) -> Vec<String> {
-    run(vec!["checkout", file_path])
+pub fn checkout_files(file_paths: &[&str]) -> Vec<String> {
+    let mut args = vec!["checkout", "--"];
+    args.extend(file_paths);
+
+    run(args)
+}
+
+// Resolves a conflicted file by taking it whole from one side
+// of the merge, side being either --ours or --theirs.
+pub fn checkout_conflict_side(path: &str, side: &str) -> Vec<String> {
+    match try_run(vec!["checkout", side, "--", path]) {
+        Some(_) => run(vec!["add", "--", path]),
+        // The file was deleted on that side.
+        None => run(vec!["rm", "--quiet", "--", path])
+    }
+}
+
+pub fn mark_resolved(path: &str) -> Vec<String> {
+    run(vec!["add", "--", path])
 }
 
-pub fn delete_branch(branch_name: &str) -> Vec<String> {
-    run(vec!["branch", "-D", branch_name])
+// Fails with the error output if any of the branches could not be deleted,
+// e.g. because it is not fully merged, the others are deleted regardless.
+pub fn delete_branches(branch_names: &[&str]) -> Result<Vec<String>, Vec<String>> {
+    let mut args = vec!["branch", "--delete"];
+    args.extend(branch_names);
+
+    run_or_error(args)
+}
+
+pub fn force_delete_branches(branch_names: &[&str]) -> Vec<String> {
+    let mut args = vec!["branch", "-D"];
+    args.extend(branch_names);
+
+    run(args)
 }
 
 // This func should actually be called branch,
 // as in, the verb.
-pub fn create_branch(branch_name: &str) -> Vec<String> {
-    run(vec!["branch", branch_name])
+pub fn create_branch(branch_name: &str, start_point: Option<&str>) -> Vec<String> {
+    let mut args = vec!["branch", branch_name];
+
+    if let Some(start_point) = start_point {
+        args.push(start_point);
+    }
+
+    run(args)
+}
+
+pub fn rename_branch(old_name: &str, new_name: &str) -> Vec<String> {
+    run(vec!["branch", "--move", old_name, new_name])
+}
+
+pub fn set_upstream(branch_name: &str, upstream: &str) -> Vec<String> {
+    let upstream_arg = format!("--set-upstream-to={}", upstream);
+    run(vec!["branch", &upstream_arg, branch_name])
+}
+
+pub fn unset_upstream(branch_name: &str) -> Vec<String> {
+    run(vec!["branch", "--unset-upstream", branch_name])
 }
 
 pub fn reset(commit_hash: &str, mode: &str) -> Vec<String> {
     run(vec!["reset", mode, commit_hash])
 }
 
-pub fn log(max_count: Option<u32>) -> Vec<String> {
-    let mut args = vec!["--no-pager", "log", "--graph", "--oneline", "--decorate"];
+pub struct Tag {
+    pub name: String,
+    pub target: String,
+    // Only annotated tags have a message.
+    pub message: Option<String>
+}
+
+pub fn tags(sort: &str) -> Vec<Tag> {
+    let sort_arg = format!("--sort={}", sort);
+    let format = "--format=%(refname:short)%00%(objecttype)%00%(objectname:short)%00%(*objectname:short)%00%(contents:subject)";
+
+    try_run(vec!["for-each-ref", &sort_arg, format, "refs/tags"])
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split('\0').collect();
+            if fields.len() < 5 { return None }
+
+            let annotated = fields[1] == "tag";
+
+            Some(Tag {
+                name: fields[0].to_owned(),
+                target: if annotated { fields[3] } else { fields[2] }.to_owned(),
+                message: if annotated { Some(fields[4].to_owned()) } else { None }
+            })
+        })
+        .collect()
+}
+
+pub fn create_tag(name: &str, commit_hash: &str, message: Option<&str>) -> Vec<String> {
+    let mut args = vec!["tag"];
+
+    if let Some(message) = message {
+        args.extend(vec!["--annotate", "--message", message]);
+    }
+
+    args.extend(vec![name, commit_hash]);
+
+    run(args)
+}
+
+pub fn delete_tag(name: &str) -> Vec<String> {
+    run(vec!["tag", "--delete", name])
+}
+
+pub fn delete_remote_tag(remote: &str, name: &str) -> Vec<String> {
+    let tag_ref = format!("refs/tags/{}", name);
+    run(vec!["push", "--delete", remote, &tag_ref])
+}
+
+pub fn push_tag(remote: &str, name: &str) -> Vec<String> {
+    let tag_ref = format!("refs/tags/{}", name);
+    run(vec!["push", remote, &tag_ref])
+}
+
+pub fn push_tags(remote: &str) -> Vec<String> {
+    run(vec!["push", remote, "--tags"])
+}
+
+pub fn has_parent(commit_hash: &str) -> bool {
+    try_run(vec!["rev-parse", "--verify", "--quiet", &format!("{}^", commit_hash)]).is_some()
+}
+
+// Lists the commits a rebase starting at the given commit (inclusive)
+// would replay, oldest first, the same way git would put them into the todo list.
+pub fn rebase_commits(commit_hash: &str) -> Vec<String> {
+    let range = if has_parent(commit_hash) {
+        format!("{}^..HEAD", commit_hash)
+    } else {
+        "HEAD".to_owned()
+    };
+
+    try_run(vec!["log", "--reverse", "--no-merges", "--format=%h %s", &range]).unwrap_or_default()
+}
+
+// Starts an interactive rebase with the todo list taken from the given file
+// instead of opening it in an editor. Without a base, the whole history is rebased.
+// Returns the error output, e.g. why git refused to start or where it stopped.
+pub fn rebase_interactive(base: Option<&str>, todo_path: &str) -> Vec<String> {
+    let mut args = vec!["rebase", "--interactive"];
+    args.push(base.unwrap_or("--root"));
+
+    let sequence_editor = format!("cp {}", shell_quote(todo_path));
+    run_attached(args, vec![("GIT_SEQUENCE_EDITOR", &sequence_editor)])
+}
+
+// Whether the tracked files or the index have changes that are not committed.
+pub fn has_uncommitted_changes() -> bool {
+    !run(vec!["status", "--porcelain", "--untracked-files=no"])
+        .iter()
+        .all(|l| l.is_empty())
+}
+
+// The branch line of the short status, with how far it is ahead of or behind its upstream.
+pub fn branch_status() -> Vec<String> {
+    run(vec!["status", "--short", "--branch"])
+        .into_iter()
+        .take(1)
+        .collect()
+}
+
+pub struct RebaseState {
+    pub head_name: String,
+    pub onto: String,
+    pub step: String,
+    pub total: String
+}
+
+pub fn rebase_state() -> Option<RebaseState> {
+    // rebase-merge is used by interactive and merge based rebases,
+    // rebase-apply by the older apply backend.
+    let (dir, step_file, total_file) = [("rebase-merge", "msgnum", "end"), ("rebase-apply", "next", "last")]
+        .iter()
+        .map(|(dir, step, total)| (git_path(dir), *step, *total))
+        .find(|(dir, _, _)| dir.is_dir())?;
+
+    let read = |name: &str| {
+        std::fs::read_to_string(dir.join(name))
+            .map(|content| content.trim().to_owned())
+            .unwrap_or_default()
+    };
+
+    let head_name = read("head-name");
+    let onto = read("onto");
+
+    Some(RebaseState {
+        head_name: head_name.trim_start_matches("refs/heads/").to_owned(),
+        onto: onto.chars().take(7).collect(),
+        step: read(step_file),
+        total: read(total_file)
+    })
+}
+
+// A multi step operation that stopped and waits for the
+// user to either continue or abort it.
+pub enum Operation {
+    Rebase(RebaseState),
+    Merge(String),
+    CherryPick(String),
+    Revert(String)
+}
+
+impl Operation {
+    pub fn name(&self) -> &str {
+        match self {
+            Operation::Rebase(_)     => "Rebase",
+            Operation::Merge(_)      => "Merge",
+            Operation::CherryPick(_) => "Cherry-pick",
+            Operation::Revert(_)     => "Revert"
+        }
+    }
+
+    pub fn command(&self) -> &str {
+        match self {
+            Operation::Rebase(_)     => "rebase",
+            Operation::Merge(_)      => "merge",
+            Operation::CherryPick(_) => "cherry-pick",
+            Operation::Revert(_)     => "revert"
+        }
+    }
+
+    pub fn description(&self) -> String {
+        match self {
+            Operation::Rebase(rebase) => {
+                format!("{} onto {} ({}/{})", rebase.head_name, rebase.onto, rebase.step, rebase.total)
+            },
+            Operation::Merge(commit) | Operation::CherryPick(commit) | Operation::Revert(commit) => {
+                commit.clone()
+            }
+        }
+    }
+
+    // Merge is the only one of these that can not skip the current commit.
+    pub fn actions(&self) -> Vec<&str> {
+        match self {
+            Operation::Merge(_) => vec!["--continue", "--abort"],
+            _ => vec!["--continue", "--skip", "--abort"]
+        }
+    }
+}
+
+pub fn operation_in_progress() -> Option<Operation> {
+    if let Some(rebase) = rebase_state() {
+        return Some(Operation::Rebase(rebase))
+    }
+
+    let read_head = |name: &str| -> Option<String> {
+        let content = std::fs::read_to_string(git_path(name)).ok()?;
+        let commit = content.lines().next()?.trim();
+
+        Some(
+            run(vec!["log", "-1", "--oneline", "--no-decorate", commit])
+                .first()
+                .cloned()
+                .unwrap_or_else(|| commit.to_owned())
+        )
+    };
+
+    if let Some(commit) = read_head("MERGE_HEAD") {
+        Some(Operation::Merge(commit))
+    } else if let Some(commit) = read_head("CHERRY_PICK_HEAD") {
+        Some(Operation::CherryPick(commit))
+    } else {
+        read_head("REVERT_HEAD").map(Operation::Revert)
+    }
+}
+
+pub fn continue_operation(operation: &Operation, action: &str) -> Vec<String> {
+    run_interactive(vec![operation.command(), action], vec![])
+}
+
+pub fn merge(merge_args: Option<Vec<&str>>, branch: &str) -> Vec<String> {
+    let mut args = vec!["merge", "--no-edit"];
+
+    if let Some(process_args) = merge_args {
+        args.extend(process_args);
+    }
+
+    args.push(branch);
+
+    run(args)
+}
+
+// Merge commits need the number of the parent, starting at 1,
+// the changes are taken relative to, the mainline.
+pub fn cherry_pick(commit_hash: &str, mainline: Option<usize>) -> Vec<String> {
+    let mainline = mainline.map(|m| m.to_string());
+
+    let mut args = vec!["cherry-pick"];
+    if let Some(mainline) = &mainline {
+        args.extend(["-m", mainline]);
+    }
+    args.push(commit_hash);
+
+    run(args)
+}
+
+pub fn revert(commit_hash: &str, mainline: Option<usize>) -> Vec<String> {
+    let mainline = mainline.map(|m| m.to_string());
+
+    let mut args = vec!["revert", "--no-edit"];
+    if let Some(mainline) = &mainline {
+        args.extend(["-m", mainline]);
+    }
+    args.push(commit_hash);
+
+    run(args)
+}
+
+// Resolves a path inside the .git directory, e.g. MERGE_HEAD.
+pub fn git_path(path: &str) -> std::path::PathBuf {
+    let resolved = try_run(vec!["rev-parse", "--git-path", path])
+        .and_then(|output| output.first().cloned())
+        .unwrap_or_else(|| format!(".git/{}", path));
+
+    std::path::PathBuf::from(resolved)
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum RefKind {
+    // HEAD pointing directly at the commit, i.e. a detached one.
+    Head,
+    // The branch HEAD points to.
+    CurrentBranch,
+    Local,
+    Remote,
+    Tag
+}
+
+#[derive(Clone)]
+pub struct Commit {
+    pub hash: String,
+    pub short_hash: String,
+    pub parents: Vec<String>,
+    pub author: String,
+    pub relative_date: String,
+    pub date: String,
+    pub refs: Vec<(RefKind, String)>,
+    pub subject: String
+}
+
+#[derive(Clone, Default)]
+pub struct LogFilter {
+    pub author: Option<String>,
+    pub since: Option<String>,
+    pub until: Option<String>,
+    pub grep: Option<String>,
+    pub path: Option<String>,
+    pub first_parent: bool,
+    pub no_merges: bool,
+    // --all, a branch or a range like master..feature, HEAD if none.
+    pub revision: Option<String>
+}
+
+impl LogFilter {
+    pub fn args(&self) -> Vec<String> {
+        let mut args = vec![];
+
+        let options = [
+            ("--author", &self.author),
+            ("--since", &self.since),
+            ("--until", &self.until),
+            ("--grep", &self.grep)
+        ];
+
+        for (option, value) in options.iter() {
+            if let Some(value) = value {
+                args.push(format!("{}={}", option, value));
+            }
+        }
+
+        if self.first_parent {
+            args.push("--first-parent".to_owned());
+        }
+
+        if self.no_merges {
+            args.push("--no-merges".to_owned());
+        }
+
+        if let Some(revision) = &self.revision {
+            args.push(revision.clone());
+        }
+
+        if let Some(path) = &self.path {
+            args.push("--".to_owned());
+            args.push(path.clone());
+        }
+
+        args
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.args().is_empty()
+    }
+}
+
+// Whether the revision names a single commit, unlike --all or a range.
+pub fn is_single_revision(revision: &str) -> bool {
+    let commit = format!("{}^{{commit}}", revision);
+    try_run(vec!["rev-parse", "--verify", "--quiet", "--end-of-options", &commit]).is_some()
+}
+
+// Whether log can be given the revisions, --all or a revision or range git resolves.
+// Anything else starting with a dash would be taken for an option.
+pub fn is_valid_log_revision(revision: &str) -> bool {
+    if revision == "--all" { return true }
+
+    !revision.starts_with('-') && try_run(vec!["rev-parse", "--end-of-options", revision]).is_some()
+}
+
+// Commits up to the revision which changed the file, following it
+// through renames, along with the path the file had in each of them.
+pub fn file_history(path: &str, revision: Option<&str>) -> Vec<(Commit, String)> {
+    let format = "--format=%x1e%H%x00%h%x00%P%x00%an%x00%ar%x00%ad%x00%D%x00%s";
+    let mut args = vec!["--no-pager", "log", "--follow", "--name-only", "--date=short", format];
+
+    if let Some(revision) = revision {
+        args.push(revision);
+    }
+
+    args.extend(vec!["--", path]);
+
+    let remotes = remotes();
+    let mut history: Vec<(Commit, String)> = vec![];
+
+    for line in try_run(args).unwrap_or_default() {
+        if let Some(commit) = line.strip_prefix('\x1e') {
+            if let Some(commit) = parse_commit(commit, &remotes) {
+                history.push((commit, path.to_owned()));
+            }
+        } else if !line.is_empty() {
+            if let Some((_, commit_path)) = history.last_mut() {
+                *commit_path = line;
+            }
+        }
+    }
+
+    history
+}
+
+// The commits are in topological order, so that the graph can be laid out.
+pub fn log(filter: &LogFilter, max_count: Option<u32>) -> Vec<Commit> {
+    let format = "--format=%H%x00%h%x00%P%x00%an%x00%ar%x00%ad%x00%D%x00%s";
+    // With --parents the parents are rewritten to the ones touching the path.
+    let mut args = vec!["--no-pager", "log", "--topo-order", "--parents", "--date=short", format];
 
     let max_count_arg;
 
@@ -191,7 +955,172 @@ pub fn log(max_count: Option<u32>) -> Vec<String> {
         args.push(&max_count_arg);
     }
 
-    run(args)
+    let filter_args = filter.args();
+    args.extend(filter_args.iter().map(|a| a.as_str()));
+
+    let remotes = remotes();
+
+    try_run(args)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|line| parse_commit(line, &remotes))
+        .collect()
+}
+
+// Commits in the revision, HEAD if none, whose changes add or remove
+// the term, -S counting its occurrences and -G matching it as a regex
+// against the changed lines.
+pub fn pickaxe(term: &str, regex: bool, revision: Option<&str>) -> Vec<Commit> {
+    let format = "--format=%H%x00%h%x00%P%x00%an%x00%ar%x00%ad%x00%D%x00%s";
+    let pickaxe_arg = format!("{}{}", if regex { "-G" } else { "-S" }, term);
+    let mut args = vec!["--no-pager", "log", "--date=short", format, &pickaxe_arg];
+
+    if let Some(revision) = revision {
+        args.push(revision);
+    }
+
+    let remotes = remotes();
+
+    try_run(args)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|line| parse_commit(line, &remotes))
+        .collect()
+}
+
+pub struct GrepMatch {
+    pub path: String,
+    // Number of the matching line, starting at 1.
+    pub line: usize,
+    pub content: String
+}
+
+// Lines matching the regex in the files as of the revision.
+pub fn grep(term: &str, revision: &str) -> Vec<GrepMatch> {
+    let prefix = format!("{}:", revision);
+
+    try_run(vec!["--no-pager", "grep", "-n", "-I", "--full-name", "-z", "-e", term, revision])
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.splitn(3, '\0').collect();
+            if fields.len() < 3 { return None }
+
+            Some(GrepMatch {
+                path: fields[0].strip_prefix(&prefix).unwrap_or(fields[0]).to_owned(),
+                line: fields[1].parse().ok()?,
+                content: fields[2].to_owned()
+            })
+        })
+        .collect()
+}
+
+fn parse_commit(line: &str, remotes: &[String]) -> Option<Commit> {
+    let fields: Vec<&str> = line.split('\0').collect();
+    if fields.len() < 8 { return None }
+
+    let refs = fields[6]
+        .split(", ")
+        .filter(|r| !r.is_empty())
+        .map(|r| {
+            if let Some(branch) = r.strip_prefix("HEAD -> ") {
+                (RefKind::CurrentBranch, branch.to_owned())
+            } else if r == "HEAD" {
+                (RefKind::Head, r.to_owned())
+            } else if let Some(tag) = r.strip_prefix("tag: ") {
+                (RefKind::Tag, tag.to_owned())
+            } else if remotes.iter().any(|remote| r.starts_with(&format!("{}/", remote))) {
+                (RefKind::Remote, r.to_owned())
+            } else {
+                (RefKind::Local, r.to_owned())
+            }
+        })
+        .collect();
+
+    Some(Commit {
+        hash: fields[0].to_owned(),
+        short_hash: fields[1].to_owned(),
+        parents: fields[2].split_whitespace().map(str::to_owned).collect(),
+        author: fields[3].to_owned(),
+        relative_date: fields[4].to_owned(),
+        date: fields[5].to_owned(),
+        refs,
+        subject: fields[7].to_owned()
+    })
+}
+
+#[derive(Clone, Default)]
+pub struct BlameLine {
+    pub hash: String,
+    // Number of the line in the file as of the commit, starting at 1.
+    pub original_line: usize,
+    pub author: String,
+    // Author date as unix time.
+    pub author_time: u64,
+    pub summary: String,
+    // Path of the file in the commit.
+    pub path: String,
+    // Parent of the commit and the path the file had in it,
+    // None if the commit added the file or is not committed yet.
+    pub previous: Option<(String, String)>,
+    pub content: String
+}
+
+impl BlameLine {
+    // Lines changed in the working tree are blamed on the zero hash.
+    pub fn is_committed(&self) -> bool {
+        self.hash.chars().any(|c| c != '0')
+    }
+}
+
+// Blames the lines of the file as of the revision, the working tree if
+// none. Commit details are only given for the first line of each commit
+// in the porcelain format, so they are copied over to the rest of them.
+pub fn blame(path: &str, revision: Option<&str>) -> Option<Vec<BlameLine>> {
+    let mut args = vec!["blame", "--porcelain"];
+
+    if let Some(revision) = revision {
+        args.push(revision);
+    }
+
+    args.extend(vec!["--", path]);
+
+    let mut commits: HashMap<String, BlameLine> = HashMap::new();
+    let mut current: Option<(String, usize)> = None;
+    let mut lines = vec![];
+
+    for line in try_run(args)? {
+        if let Some(content) = line.strip_prefix('\t') {
+            if let Some((hash, original_line)) = current.take() {
+                let commit = commits.get(&hash).cloned().unwrap_or_default();
+
+                lines.push(BlameLine { hash, original_line, content: content.to_owned(), ..commit });
+            }
+        } else if let Some((hash, _)) = &current {
+            let (key, value) = line.split_once(' ').unwrap_or((&line, ""));
+            let commit = commits.entry(hash.clone()).or_default();
+
+            match key {
+                "author" => commit.author = value.to_owned(),
+                "author-time" => commit.author_time = value.parse().unwrap_or(0),
+                "summary" => commit.summary = value.to_owned(),
+                "filename" => commit.path = value.to_owned(),
+                "previous" => commit.previous = value
+                    .split_once(' ')
+                    .map(|(hash, path)| (hash.to_owned(), path.to_owned())),
+                _ => {}
+            }
+        } else {
+            // <hash> <original line> <final line> [<lines in group>]
+            let fields: Vec<&str> = line.split_whitespace().collect();
+
+            if fields.len() >= 3 {
+                current = Some((fields[0].to_owned(), fields[1].parse().unwrap_or(1)));
+            }
+        }
+    }
+
+    Some(lines)
 }
 
 pub fn run(args: Vec<&str>) -> Vec<String> {
@@ -203,6 +1132,91 @@ pub fn run(args: Vec<&str>) -> Vec<String> {
     output_lines(output)
 }
 
+// Runs git attached to the terminal, so that an editor
+// can be opened if the command needs one.
+fn run_interactive(args: Vec<&str>, envs: Vec<(&str, &str)>) -> Vec<String> {
+    let output = std::process::Command::new("git")
+        .args(args)
+        .envs(envs)
+        .spawn()
+        .unwrap()
+        .wait_with_output()
+        .expect("failed to execute process");
+
+    output_lines(output)
+}
+
+// Same as run_interactive, but the error output is returned instead of
+// being printed to the terminal, where the windows would draw over it.
+fn run_attached(args: Vec<&str>, envs: Vec<(&str, &str)>) -> Vec<String> {
+    let output = std::process::Command::new("git")
+        .args(args)
+        .envs(envs)
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .unwrap()
+        .wait_with_output()
+        .expect("failed to execute process");
+
+    String::from_utf8_lossy(&output.stderr)
+        .lines()
+        .map(str::to_owned)
+        .collect()
+}
+
+// Quotes the value for sh, e.g. for commands git runs through the shell.
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+// Same as run, but returns None if the command failed instead
+// of the error output. Trailing empty lines are dropped.
+pub fn try_run(args: Vec<&str>) -> Option<Vec<String>> {
+    let output = std::process::Command::new("git")
+        .args(args)
+        .output()
+        .expect("failed to execute process");
+
+    if !output.status.success() {
+        return None
+    }
+
+    let output_str = String::from_utf8(output.stdout).expect("invalid string encoding");
+
+    Some(
+        output_str
+            .lines()
+            .map(str::to_owned)
+            .collect()
+    )
+}
+
+// Same as run, but tells a failed command apart, returning what
+// it printed before failing followed by its error output.
+fn run_or_error(args: Vec<&str>) -> Result<Vec<String>, Vec<String>> {
+    let output = std::process::Command::new("git")
+        .args(args)
+        .output()
+        .expect("failed to execute process");
+
+    if output.status.success() {
+        return Ok(output_lines(output))
+    }
+
+    let lines = |bytes: Vec<u8>| -> Vec<String> {
+        String::from_utf8(bytes)
+            .expect("invalid string encoding")
+            .lines()
+            .map(str::to_owned)
+            .collect()
+    };
+
+    let mut error = lines(output.stdout);
+    error.extend(lines(output.stderr));
+
+    Err(error)
+}
+
 fn output_lines(output: std::process::Output) -> Vec<String> {
     let descriptor = if output.stdout.is_empty() { output.stderr } else { output.stdout };
     let output_str = String::from_utf8(descriptor).expect("invalid string encoding");
@@ -0,0 +1,232 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::git;
+use crate::git::BlameLine;
+use crate::screen;
+use crate::gitko::output_window::show_output;
+use crate::gitko::commit_detail_window::CommitDetailWindow;
+use crate::gitko::file_revision_window::FileRevisionWindow;
+use crate::searchable::{SearchableComponent, register_search_handlers};
+use gitko_render::{Component, KeyHandlers, Line, Renderer, ScreenSize, Window, Position, Part, Style};
+
+use gitko_common::ascii_table::{KEY_LF, KEY_O_LOWER, KEY_P_LOWER, KEY_P_UPPER};
+
+// Title and an empty line are rendered above the blamed lines.
+const HEADER_LINES: usize = 2;
+
+const MAX_AUTHOR_WIDTH: usize = 20;
+
+// A blamed revision of a file, stepped back from with blame parent.
+struct BlameState {
+    path: String,
+    revision: Option<String>,
+    cursor_index: usize
+}
+
+pub struct BlameWindow {
+    path: String,
+    // The commit to blame the file as of, the working tree if none.
+    revision: Option<String>,
+    lines: Vec<BlameLine>,
+    // Blames left with blame parent, the latest last.
+    previous: Vec<BlameState>,
+    // Line to put the cursor on once the blame is rendered.
+    cursor_index: Option<usize>
+}
+
+impl BlameWindow {
+    pub fn new(path: &str, revision: Option<&str>) -> BlameWindow {
+        BlameWindow {
+            path: path.to_owned(),
+            revision: revision.map(str::to_owned),
+            lines: vec![],
+            previous: vec![],
+            cursor_index: None
+        }
+    }
+
+    fn selected_line(&self, window: &Window) -> Option<&BlameLine> {
+        window
+            .cursor_index()
+            .checked_sub(HEADER_LINES)
+            .and_then(|i| self.lines.get(i))
+    }
+
+    fn open_commit(&mut self, window: &mut Window) -> bool {
+        if let Some(line) = self.selected_line(window).filter(|l| l.is_committed()) {
+            Renderer::new(
+                &mut CommitDetailWindow::new(&line.hash),
+                ScreenSize::max(),
+                Position::default(),
+                screen()
+            ).render();
+        }
+
+        true
+    }
+
+    fn open_file(&mut self, window: &mut Window) -> bool {
+        if let Some(line) = self.selected_line(window).filter(|l| l.is_committed()) {
+            Renderer::new(
+                &mut FileRevisionWindow::new(&line.hash, &line.path, Some(line.original_line)),
+                ScreenSize::max(),
+                Position::default(),
+                screen()
+            ).render();
+        }
+
+        true
+    }
+
+    // Blames the file as of the parent of the commit of the line under
+    // the cursor, to get past commits which only reformatted the line.
+    fn blame_parent(&mut self, window: &mut Window) -> bool {
+        let line = match self.selected_line(window) {
+            Some(line) if line.is_committed() => line.clone(),
+            _ => return true
+        };
+
+        let (parent, path) = match line.previous {
+            Some(previous) => previous,
+            None => {
+                show_output(vec![format!("Commit {} added the line, it has no parent to blame.", short_hash(&line.hash))]);
+                return true
+            }
+        };
+
+        self.previous.push(BlameState {
+            path: std::mem::replace(&mut self.path, path),
+            revision: self.revision.replace(parent),
+            cursor_index: window.cursor_index()
+        });
+
+        // The line most likely kept its number in the parent.
+        self.cursor_index = Some(HEADER_LINES + line.original_line - 1);
+        self.on_start(window);
+
+        true
+    }
+
+    fn blame_previous(&mut self, window: &mut Window) -> bool {
+        if let Some(state) = self.previous.pop() {
+            self.path = state.path;
+            self.revision = state.revision;
+            self.cursor_index = Some(state.cursor_index);
+            self.on_start(window);
+        }
+
+        true
+    }
+}
+
+fn short_hash(hash: &str) -> &str {
+    &hash[..8.min(hash.len())]
+}
+
+// Compact age like 5d or 3mo, along with the color
+// of the age, the more recent the brighter.
+fn age(seconds: u64) -> (String, (u8, u8, u8)) {
+    let days = seconds / (24 * 60 * 60);
+
+    let text = match days {
+        0 => format!("{}h", seconds / (60 * 60)),
+        1..=13 => format!("{}d", days),
+        14..=59 => format!("{}w", days / 7),
+        60..=729 => format!("{}mo", days / 30),
+        _ => format!("{}y", days / 365)
+    };
+
+    let color = match days {
+        0..=6 => (0, 255, 0),
+        7..=29 => (0, 255, 255),
+        30..=364 => (100, 150, 255),
+        _ => (150, 150, 150)
+    };
+
+    (text, color)
+}
+
+impl Component<BlameWindow> for BlameWindow {
+    fn on_start(&mut self, window: &mut Window) {
+        let at = self.revision
+            .as_deref()
+            .map_or("working tree".to_owned(), |r| short_hash(r).to_owned());
+
+        let title = Line::from_str(
+            &format!("Blame of {} at {}: (Enter: commit, o: open file, p: blame parent, P: back)", self.path, at),
+            Some(vec![Style::Bold, Style::Underlined])
+        );
+
+        match git::blame(&self.path, self.revision.as_deref()) {
+            Some(lines) => self.lines = lines,
+            None => {
+                self.lines = vec![];
+                window.set_lines(vec![title, Line::empty(), Line::plain("The file could not be blamed.")]);
+
+                return
+            }
+        }
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        let author_width = self.lines
+            .iter()
+            .map(|l| l.author.chars().count())
+            .max()
+            .unwrap_or(0)
+            .min(MAX_AUTHOR_WIDTH);
+        let number_width = self.lines.len().to_string().len();
+
+        let mut lines = vec![title, Line::empty()];
+
+        for (index, line) in self.lines.iter().enumerate() {
+            // The commit is only shown on the first of its consecutive lines.
+            let same_commit = index > 0 && self.lines[index - 1].hash == line.hash;
+
+            let mut parts = if same_commit {
+                vec![Part::plain(&" ".repeat(8 + 1 + author_width + 1 + 4 + 1))]
+            } else {
+                let (age, color) = if line.is_committed() {
+                    age(now.saturating_sub(line.author_time))
+                } else {
+                    ("now".to_owned(), (255, 255, 255))
+                };
+                let author: String = line.author.chars().take(author_width).collect();
+
+                vec![
+                    Part::painted(short_hash(&line.hash), color, (0, 0, 0)),
+                    Part::plain(" "),
+                    Part::painted(&format!("{:<width$}", author, width = author_width), color, (0, 0, 0)),
+                    Part::plain(" "),
+                    Part::painted(&format!("{:>4}", age), color, (0, 0, 0)),
+                    Part::plain(" ")
+                ]
+            };
+
+            parts.push(Part::painted(&format!("{:>width$} ", index + 1, width = number_width), (150, 150, 150), (0, 0, 0)));
+            parts.push(Part::plain(&line.content.replace('\t', "    ")));
+
+            lines.push(Line::new(parts));
+        }
+
+        window.set_lines(lines);
+
+        if let Some(index) = self.cursor_index.take() {
+            window.move_cursor_to(index.min(HEADER_LINES + self.lines.len().saturating_sub(1)));
+        }
+    }
+
+    fn register_handlers(&self, handlers: &mut KeyHandlers<BlameWindow>) {
+        handlers.insert(KEY_LF, BlameWindow::open_commit);
+        handlers.insert(KEY_O_LOWER, BlameWindow::open_file);
+        handlers.insert(KEY_P_LOWER, BlameWindow::blame_parent);
+        handlers.insert(KEY_P_UPPER, BlameWindow::blame_previous);
+
+        register_search_handlers(handlers);
+    }
+}
+
+impl SearchableComponent<BlameWindow> for BlameWindow {}
@@ -0,0 +1,199 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::git;
+use crate::gitko::input_window::read_input;
+use crate::gitko::output_window::show_output;
+use crate::gitko::prompt_window::confirm;
+use crate::gitko::branch_window::delete_local_branches;
+use crate::selectable::{SelectableComponent, register_selection_handlers};
+use gitko_render::{Component, KeyHandlers, Line, Window, Part, Style};
+
+use gitko_common::ascii_table::{KEY_B_LOWER, KEY_LF, KEY_N_LOWER};
+
+// Help, settings and an empty line are rendered above the candidates.
+const HEADER_LINES: usize = 3;
+
+const SECONDS_IN_DAY: u64 = 24 * 60 * 60;
+
+struct Candidate {
+    name: String,
+    reasons: Vec<String>
+}
+
+pub struct BranchCleanupWindow {
+    base: String,
+    stale_days: u64,
+    candidates: Vec<Candidate>
+}
+
+impl BranchCleanupWindow {
+    pub fn new() -> BranchCleanupWindow {
+        let base = ["main", "master"]
+            .iter()
+            .find(|b| git::branch_exists(b))
+            .map(|b| b.to_string())
+            .unwrap_or_else(git::current_branch);
+
+        BranchCleanupWindow {
+            base,
+            stale_days: 90,
+            candidates: vec![]
+        }
+    }
+
+    fn find_candidates(&self) -> Vec<Candidate> {
+        let merged = git::merged_branches(&self.base);
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        git::branches("refname")
+            .into_iter()
+            .filter(|b| !b.is_remote && !b.is_head && b.name != self.base)
+            .filter_map(|b| {
+                let mut reasons = vec![];
+
+                if merged.contains(&b.name) {
+                    reasons.push(format!("merged into {}", self.base));
+                }
+
+                if b.track == "gone" {
+                    reasons.push("upstream gone".to_owned());
+                }
+
+                let age_days = now.saturating_sub(b.timestamp) / SECONDS_IN_DAY;
+                if age_days >= self.stale_days {
+                    reasons.push(format!("untouched for {} days", age_days));
+                }
+
+                if reasons.is_empty() {
+                    None
+                } else {
+                    Some(Candidate { name: b.name, reasons })
+                }
+            })
+            .collect()
+    }
+
+    fn change_base(&mut self, window: &mut Window) -> bool {
+        let base = read_input(&format!("Enter base branch (currently '{}'):", self.base));
+
+        if !base.is_empty() {
+            self.base = base;
+        }
+
+        self.on_start(window);
+
+        true
+    }
+
+    fn change_stale_days(&mut self, window: &mut Window) -> bool {
+        let days = read_input(&format!("Enter days after which a branch is stale (currently {}):", self.stale_days));
+
+        if let Ok(days) = days.parse() {
+            self.stale_days = days;
+        }
+
+        self.on_start(window);
+
+        true
+    }
+
+    fn delete_selected(&mut self, window: &mut Window) -> bool {
+        let branches: Vec<&str> = self.selection(window)
+            .into_iter()
+            .filter_map(|i| i.checked_sub(HEADER_LINES).and_then(|i| self.candidates.get(i)))
+            .map(|c| c.name.as_str())
+            .collect();
+
+        if branches.is_empty() { return true }
+
+        let message = format!(
+            "Are you sure you want to delete {} branches ({})? y/n",
+            branches.len(),
+            branches.join(", ")
+        );
+
+        if confirm(&message) {
+            // Branches merged into a base other than HEAD are not considered
+            // merged by git branch -d, so only their deletion is forced.
+            let merged = git::merged_branches(&self.base);
+            let (merged, unmerged): (Vec<&str>, Vec<&str>) = branches
+                .into_iter()
+                .partition(|b| merged.iter().any(|m| m == b));
+
+            if !merged.is_empty() {
+                show_output(git::force_delete_branches(&merged));
+            }
+
+            if !unmerged.is_empty() {
+                delete_local_branches(&unmerged);
+            }
+        }
+
+        self.on_start(window);
+
+        true
+    }
+
+    fn render_candidates(&mut self, window: &mut Window) {
+        let mut lines = vec![
+            Line::from_str(
+                "Branch cleanup (space/a/v: mark, b: base, n: stale days, Enter: delete marked or the one under the cursor)",
+                Some(vec![Style::Bold, Style::Underlined])
+            ),
+            Line::plain(&format!(
+                "Base: {}, stale after {} days, {} candidates",
+                self.base,
+                self.stale_days,
+                self.candidates.len()
+            )),
+            Line::empty()
+        ];
+
+        if self.candidates.is_empty() {
+            lines.push(Line::plain("No branches to clean up."));
+        }
+
+        let name_width = self.candidates.iter().map(|c| c.name.len()).max().unwrap_or(0);
+
+        lines.extend(
+            self.candidates
+                .iter()
+                .map(|c| {
+                    Line::new(vec![
+                        Part::painted(&format!("{:<width$}", c.name, width = name_width), (0, 255, 0), (0, 0, 0)),
+                        Part::plain(" "),
+                        Part::painted(&c.reasons.join(", "), (255, 255, 0), (0, 0, 0))
+                    ])
+                })
+        );
+
+        window.set_lines(lines);
+    }
+}
+
+impl Component<BranchCleanupWindow> for BranchCleanupWindow {
+    fn on_start(&mut self, window: &mut Window) {
+        self.candidates = self.find_candidates();
+        self.render_candidates(window);
+    }
+
+    fn register_handlers(&self, handlers: &mut KeyHandlers<BranchCleanupWindow>) {
+        handlers.insert(KEY_B_LOWER, BranchCleanupWindow::change_base);
+        handlers.insert(KEY_N_LOWER, BranchCleanupWindow::change_stale_days);
+        handlers.insert(KEY_LF, BranchCleanupWindow::delete_selected);
+
+        register_selection_handlers(handlers);
+    }
+}
+
+impl SelectableComponent<BranchCleanupWindow> for BranchCleanupWindow {
+    // The candidates are the only section.
+    fn section_range(&self, _window: &Window, index: usize) -> Option<(usize, usize)> {
+        index.checked_sub(HEADER_LINES).and_then(|i| self.candidates.get(i))?;
+
+        Some((HEADER_LINES, HEADER_LINES + self.candidates.len() - 1))
+    }
+}
@@ -1,45 +1,126 @@
 use crate::git;
-use crate::{screen, max_width};
-use gitko_render::{Component, KeyHandlers, Line, Renderer, ScreenSize, Window, Position};
+use crate::git::Branch;
+use crate::screen;
+use crate::selectable::{SelectableComponent, register_selection_handlers};
+use crate::searchable::{SearchableComponent, register_filter_handlers};
+use gitko_render::{Component, KeyHandlers, Line, Renderer, ScreenSize, Window, Position, Part, Style};
 
-use gitko_common::ascii_table::{KEY_D_LOWER, KEY_LF, KEY_N_LOWER};
+use gitko_common::ascii_table::{KEY_C_UPPER, KEY_D_LOWER, KEY_LF, KEY_M_LOWER, KEY_N_LOWER, KEY_R_LOWER, KEY_R_UPPER,
+                                KEY_S_LOWER, KEY_U_LOWER, KEY_U_UPPER};
 
-use crate::gitko::text_window::TextWindow;
-use crate::gitko::input_window::InputWindow;
-use crate::gitko::prompt_window::PromptWindow;
+use crate::gitko::input_window::read_input;
+use crate::gitko::prompt_window::confirm;
+use crate::gitko::output_window::show_output;
+use crate::gitko::remotes_window::RemotesWindow;
+use crate::gitko::branch_cleanup_window::BranchCleanupWindow;
 
-pub struct BranchWindow { }
+const SORT_BY_NAME: &str = "refname";
+const SORT_BY_RECENCY: &str = "-committerdate";
+
+pub struct BranchWindow {
+    sort: &'static str,
+    // The branch each rendered line shows, if any.
+    entries: Vec<Option<Branch>>
+}
 
 impl BranchWindow {
+    pub fn new() -> BranchWindow {
+        BranchWindow {
+            sort: SORT_BY_NAME,
+            entries: vec![]
+        }
+    }
+
+    fn selected_branch(&self, window: &Window) -> Option<&Branch> {
+        self.entries
+            .get(window.cursor_index())
+            .and_then(|e| e.as_ref())
+    }
+
+    fn selected_local_branch(&self, window: &Window) -> Option<String> {
+        self.selected_branch(window)
+            .filter(|b| !b.is_remote)
+            .map(|b| b.name.clone())
+    }
+
     fn open_delete_branch_prompt(&mut self, window: &mut Window) -> bool {
-        let line = window.get_cursor_line();
-        if line.trim().is_empty() { return true }
-
-        if !line.starts_with('*') {
-            let branch = line.trim();
-            let mut prompt = PromptWindow::new(
-                &format!("Are you sure you want to delete branch '{}'? y/n", branch),
-                || { git::delete_branch(branch); },
-                || { /* Do nothing on no. */ }
-            );
-
-            Renderer::new(
-                &mut prompt,
-                ScreenSize { lines: 1, cols: 0 }, // TODO
-                Position { x: 0, y: window.height() - 1 },
-                screen()
-            ).render();
-
-            self.on_start(window);
+        let names: Vec<String> = self.selection(window)
+            .into_iter()
+            .filter_map(|i| self.entries.get(i).and_then(|e| e.as_ref()))
+            .filter(|b| !b.is_head && !b.is_remote)
+            .map(|b| b.name.clone())
+            .collect();
+
+        if names.is_empty() { return true }
+
+        let names: Vec<&str> = names.iter().map(|n| n.as_str()).collect();
+        let description = if names.len() == 1 {
+            format!("branch '{}'", names[0])
+        } else {
+            format!("{} branches ({})", names.len(), names.join(", "))
+        };
+
+        if confirm(&format!("Are you sure you want to delete {}? y/n", description)) {
+            delete_local_branches(&names);
+        }
+
+        self.on_start(window);
+
+        true
+    }
+
+    fn rename_branch(&mut self, window: &mut Window) -> bool {
+        if let Some(name) = self.selected_local_branch(window) {
+            let new_name = read_input(&format!("Enter new name of the branch '{}':", name));
+
+            if !new_name.is_empty() {
+                show_output(git::rename_branch(&name, &new_name));
+            }
+        }
+
+        self.on_start(window);
+
+        true
+    }
+
+    fn set_upstream(&mut self, window: &mut Window) -> bool {
+        if let Some(name) = self.selected_local_branch(window) {
+            let upstream = read_input(&format!("Enter upstream of the branch '{}', e.g. origin/{}:", name, name));
+
+            if !upstream.is_empty() {
+                show_output(git::set_upstream(&name, &upstream));
+            }
         }
 
+        self.on_start(window);
+
+        true
+    }
+
+    fn unset_upstream(&mut self, window: &mut Window) -> bool {
+        if let Some(name) = self.selected_local_branch(window) {
+            show_output(git::unset_upstream(&name));
+        }
+
+        self.on_start(window);
+
+        true
+    }
+
+    fn toggle_sort(&mut self, window: &mut Window) -> bool {
+        self.sort = if self.sort == SORT_BY_NAME { SORT_BY_RECENCY } else { SORT_BY_NAME };
+        self.on_start(window);
+
         true
     }
 
     fn checkout_branch(&mut self, window: &mut Window) -> bool {
-        let line = window.get_cursor_line();
-        if !line.starts_with('*') {
-            git::checkout_branch(line.trim());
+        if let Some(branch) = self.selected_branch(window) {
+            if branch.is_remote {
+                show_output(git::checkout_remote_branch(&branch.name));
+            } else if !branch.is_head {
+                git::checkout_branch(&branch.name);
+            }
         }
 
         self.on_start(window);
@@ -47,25 +128,106 @@ impl BranchWindow {
         true
     }
 
-    fn create_branch(&mut self, window: &mut Window) -> bool {
+    fn open_merge_options(&mut self, window: &mut Window) -> bool {
+        let branch = match self.selected_branch(window) {
+            Some(branch) if !branch.is_head => branch.name.clone(),
+            _ => return true
+        };
+
+        Renderer::new(
+            &mut MergeOptionsWindow { branch },
+            ScreenSize { lines: 5, cols: window.width() },
+            Position { x: 0, y: window.height() - 5 },
+            screen()
+        ).render();
+
+        self.on_start(window);
+
+        true
+    }
+
+    fn open_remotes_window(&mut self, window: &mut Window) -> bool {
         Renderer::new(
-            &mut TextWindow {
-                lines: vec!["Enter new branch name:"]
-            },
-            ScreenSize { lines: 5, cols: max_width() }, // TODO
-            Position { x: 0, y: window.height() - 2 },
+            &mut RemotesWindow::new(),
+            ScreenSize::max(),
+            Position::default(),
             screen()
-        ).draw();
+        ).render();
 
-        let mut input_window = InputWindow::new();
+        self.on_start(window);
+
+        true
+    }
+
+    fn open_cleanup_window(&mut self, window: &mut Window) -> bool {
         Renderer::new(
-            &mut input_window,
-            ScreenSize { lines: 2, cols: max_width() },
-            Position { x: 0, y: window.height() - 1 },
+            &mut BranchCleanupWindow::new(),
+            ScreenSize::max(),
+            Position::default(),
             screen()
         ).render();
 
-        git::create_branch(&input_window.text);
+        self.on_start(window);
+
+        true
+    }
+
+    fn branch_section(&mut self, title: &str, branches: Vec<Branch>) -> Vec<Line> {
+        let mut lines = vec![
+            Line::from_str(
+                &format!("{}: ({})", title, branches.len()),
+                Some(vec![Style::Bold, Style::Underlined])
+            )
+        ];
+        self.entries.push(None);
+
+        let name_width = branches.iter().map(|b| b.name.len()).max().unwrap_or(0);
+        let upstream_width = branches
+            .iter()
+            .map(|b| b.upstream.as_ref().map_or(0, |u| u.len()))
+            .max()
+            .unwrap_or(0);
+        let date_width = branches.iter().map(|b| b.date.len()).max().unwrap_or(0);
+        let author_width = branches.iter().map(|b| b.author.len()).max().unwrap_or(0);
+
+        for branch in branches {
+            let name = format!("{} {:<width$}", if branch.is_head { "*" } else { " " }, branch.name, width = name_width);
+            let name_color = if branch.is_remote { (255, 0, 0) } else { (0, 255, 0) };
+
+            let mut parts = vec![
+                Part::painted(&name, name_color, (0, 0, 0)),
+                Part::plain(" ")
+            ];
+
+            if upstream_width > 0 {
+                let upstream = branch.upstream.clone().unwrap_or_default();
+                parts.push(Part::painted(&format!("{:<width$}", upstream, width = upstream_width), (0, 255, 255), (0, 0, 0)));
+                parts.push(Part::plain(" "));
+            }
+
+            if !branch.track.is_empty() {
+                parts.push(Part::painted(&format!("[{}] ", branch.track), (255, 255, 0), (0, 0, 0)));
+            }
+
+            parts.push(Part::painted(&format!("{:<width$}", branch.date, width = date_width), (0, 255, 255), (0, 0, 0)));
+            parts.push(Part::plain(" "));
+            parts.push(Part::painted(&format!("{:<width$}", branch.author, width = author_width), (255, 255, 0), (0, 0, 0)));
+            parts.push(Part::plain(" "));
+            parts.push(Part::plain(&branch.subject));
+
+            lines.push(Line::new(parts));
+            self.entries.push(Some(branch));
+        }
+
+        lines
+    }
+
+    fn create_branch(&mut self, window: &mut Window) -> bool {
+        let name = read_input("Enter new branch name:");
+
+        if !name.is_empty() {
+            git::create_branch(&name, None);
+        }
 
         self.on_start(window);
 
@@ -75,17 +237,212 @@ impl BranchWindow {
 
 impl Component<BranchWindow> for BranchWindow {
     fn on_start(&mut self, window: &mut Window) {
-        window.set_lines(
-            git::branch()
-                .iter()
-                .map(|l| Line::plain(l))
-                .collect()
-        );
+        let (remote, local): (Vec<Branch>, Vec<Branch>) = git::branches(self.sort)
+            .into_iter()
+            .partition(|b| b.is_remote);
+
+        self.entries = vec![];
+
+        let sort_name = if self.sort == SORT_BY_NAME { "name" } else { "recency" };
+        let mut lines = vec![
+            Line::plain(&format!(
+                "Sorted by {} (s: sort, r: rename, u/U: set/unset upstream, m: merge, R: remotes, C: cleanup, space/a/v: mark, C-f: filter)",
+                sort_name
+            )),
+            Line::empty()
+        ];
+        self.entries.extend(vec![None, None]);
+
+        lines.append(&mut self.branch_section("Local branches", local));
+
+        lines.push(Line::empty());
+        self.entries.push(None);
+
+        lines.append(&mut self.branch_section("Remote branches", remote));
+
+        // The filter matches the branch names only, hiding the other lines.
+        let lines = lines
+            .into_iter()
+            .zip(&self.entries)
+            .map(|(line, entry)| line.with_key(entry.as_ref().map_or("", |b| &b.name)))
+            .collect();
+
+        window.set_lines(lines);
     }
 
     fn register_handlers(&self, handlers: &mut KeyHandlers<BranchWindow>) {
         handlers.insert(KEY_D_LOWER, BranchWindow::open_delete_branch_prompt);
         handlers.insert(KEY_LF, BranchWindow::checkout_branch);
         handlers.insert(KEY_N_LOWER, BranchWindow::create_branch);
+        handlers.insert(KEY_M_LOWER, BranchWindow::open_merge_options);
+        handlers.insert(KEY_R_UPPER, BranchWindow::open_remotes_window);
+        handlers.insert(KEY_C_UPPER, BranchWindow::open_cleanup_window);
+        handlers.insert(KEY_R_LOWER, BranchWindow::rename_branch);
+        handlers.insert(KEY_S_LOWER, BranchWindow::toggle_sort);
+        handlers.insert(KEY_U_LOWER, BranchWindow::set_upstream);
+        handlers.insert(KEY_U_UPPER, BranchWindow::unset_upstream);
+
+        register_selection_handlers(handlers);
+        register_filter_handlers(handlers);
+    }
+}
+
+impl SelectableComponent<BranchWindow> for BranchWindow {
+    fn section_range(&self, _window: &Window, index: usize) -> Option<(usize, usize)> {
+        self.entries.get(index)?.as_ref()?;
+
+        let start = self.entries[..index]
+            .iter()
+            .rposition(|e| e.is_none())
+            .map_or(0, |p| p + 1);
+        let end = self.entries[index..]
+            .iter()
+            .position(|e| e.is_none())
+            .map_or(self.entries.len(), |p| index + p) - 1;
+
+        Some((start, end))
+    }
+}
+
+impl SearchableComponent<BranchWindow> for BranchWindow {
+    fn fuzzy_filter(&self) -> bool {
+        true
+    }
+}
+
+// Deletes the branches, asking whether to force the deletion of the ones
+// that are not fully merged. Shows what was deleted and any errors.
+pub fn delete_local_branches(names: &[&str]) {
+    let (merged, unmerged) = split_unmerged(names, &git::unmerged_branches("HEAD"));
+
+    if !merged.is_empty() {
+        // Errors, e.g. for a branch checked out in a worktree, follow the deleted branches.
+        match git::delete_branches(&merged) {
+            Ok(output) | Err(output) => show_output(output)
+        }
+    }
+
+    if !unmerged.is_empty()
+        && confirm(&format!("Not fully merged: {}. Delete anyway? y/n", unmerged.join(", "))) {
+        show_output(git::force_delete_branches(&unmerged));
+    }
+}
+
+// Splits the branches into the merged and the unmerged ones, keeping their order.
+fn split_unmerged<'a>(names: &[&'a str], unmerged: &[String]) -> (Vec<&'a str>, Vec<&'a str>) {
+    names
+        .iter()
+        .copied()
+        .partition(|n| !unmerged.iter().any(|u| u == n))
+}
+
+struct MergeOptionsWindow {
+    branch: String
+}
+
+impl MergeOptionsWindow {
+    fn git_merge(&mut self, window: &mut Window) -> bool {
+        let line = window.get_cursor_line().trim().to_owned();
+        if line.ends_with(':') { return true }
+
+        let args = if line.is_empty() {
+            None
+        } else {
+            Some(vec![line.as_str()])
+        };
+
+        window.clear();
+
+        show_output(git::merge(args, &self.branch));
+
+        false
+    }
+}
+
+impl Component<MergeOptionsWindow> for MergeOptionsWindow {
+    fn on_start(&mut self, window: &mut Window) {
+        let mut lines = vec![
+            Line::from_str(
+                &format!("Merge '{}' into the current branch:", self.branch),
+                Some(vec![Style::Bold, Style::Underlined])
+            )
+        ];
+
+        lines.extend(
+            ["", "--no-ff", "--squash", "--ff-only"]
+                .iter()
+                .map(|s| Line::plain(s))
+        );
+
+        window.set_lines(lines);
+    }
+
+    fn register_handlers(&self, handlers: &mut KeyHandlers<MergeOptionsWindow>) {
+        handlers.insert(KEY_LF, MergeOptionsWindow::git_merge);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::split_unmerged;
+    use crate::git;
+
+    use std::path::Path;
+    use std::process::Command;
+
+    fn run_git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(["-c", "user.name=gitko", "-c", "user.email=gitko@example.com"])
+            .args(args)
+            .current_dir(dir)
+            .status()
+            .unwrap();
+
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    #[test]
+    fn split_unmerged_keeps_the_order() {
+        let unmerged = vec!["b".to_owned(), "d".to_owned()];
+
+        let (merged, unmerged) = split_unmerged(&["a", "b", "c", "d"], &unmerged);
+
+        assert_eq!(merged, vec!["a", "c"]);
+        assert_eq!(unmerged, vec!["b", "d"]);
+    }
+
+    // A marked selection mixing merged and unmerged branches deletes the merged
+    // ones, leaving exactly the unmerged ones for the force prompt.
+    #[test]
+    fn mixed_selection_deletes_the_merged_branches() {
+        let dir = std::env::temp_dir().join(format!("gitko-branch-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        run_git(&dir, &["init", "-q", "-b", "main"]);
+        run_git(&dir, &["commit", "-q", "--allow-empty", "-m", "first"]);
+        run_git(&dir, &["branch", "merged"]);
+        run_git(&dir, &["branch", "also-merged"]);
+        run_git(&dir, &["switch", "-q", "-c", "unmerged"]);
+        run_git(&dir, &["commit", "-q", "--allow-empty", "-m", "second"]);
+        run_git(&dir, &["switch", "-q", "main"]);
+
+        std::env::set_current_dir(&dir).unwrap();
+
+        let marked = ["merged", "unmerged", "also-merged"];
+        let (merged, unmerged) = split_unmerged(&marked, &git::unmerged_branches("HEAD"));
+
+        assert_eq!(merged, vec!["merged", "also-merged"]);
+        assert_eq!(unmerged, vec!["unmerged"]);
+
+        let output = git::delete_branches(&merged).unwrap();
+        assert!(output.iter().any(|l| l.starts_with("Deleted branch merged")));
+        assert!(output.iter().any(|l| l.starts_with("Deleted branch also-merged")));
+
+        assert!(!git::branch_exists("merged"));
+        assert!(!git::branch_exists("also-merged"));
+        assert!(git::branch_exists("unmerged"));
+
+        let _ = std::fs::remove_dir_all(&dir);
     }
 }
@@ -0,0 +1,257 @@
+use crate::git;
+use crate::git::{CommitDetails, FileStat};
+use crate::screen;
+use crate::gitko::commit_diff_window::CommitDiffWindow;
+use crate::gitko::blame_window::BlameWindow;
+use crate::gitko::file_revision_window::FileRevisionWindow;
+use crate::gitko::file_history_window::FileHistoryWindow;
+use crate::searchable::{SearchableComponent, register_search_handlers};
+use gitko_render::{Component, KeyHandlers, Line, Renderer, ScreenSize, Window, Position, Part, Style};
+
+use gitko_common::ascii_table::{KEY_B_LOWER, KEY_D_LOWER, KEY_H_LOWER, KEY_LF, KEY_O_LOWER, KEY_P_LOWER};
+
+pub struct CommitDetailWindow {
+    commit_hash: String,
+    details: Option<CommitDetails>,
+    // Index of the parent the changes are compared to, merges have several.
+    parent_index: usize,
+    // The changed file each rendered line shows, if any.
+    entries: Vec<Option<String>>
+}
+
+impl CommitDetailWindow {
+    pub fn new(commit_hash: &str) -> CommitDetailWindow {
+        CommitDetailWindow {
+            commit_hash: commit_hash.to_owned(),
+            details: None,
+            parent_index: 0,
+            entries: vec![]
+        }
+    }
+
+    fn parent(&self) -> Option<String> {
+        self.details
+            .as_ref()
+            .and_then(|d| d.parents.get(self.parent_index))
+            .cloned()
+    }
+
+    fn open_diff(&mut self, path: Option<&str>) {
+        let paths: Vec<&str> = path.into_iter().collect();
+
+        Renderer::new(
+            &mut CommitDiffWindow::new(&self.commit_hash, self.parent().as_deref(), &paths),
+            ScreenSize::max(),
+            Position::default(),
+            screen()
+        ).render();
+    }
+
+    fn open_file_diff(&mut self, window: &mut Window) -> bool {
+        let path = self.entries
+            .get(window.cursor_index())
+            .cloned()
+            .flatten();
+
+        self.open_diff(path.as_deref());
+
+        true
+    }
+
+    fn open_file_history(&mut self, window: &mut Window) -> bool {
+        if let Some(Some(path)) = self.entries.get(window.cursor_index()) {
+            Renderer::new(
+                &mut FileHistoryWindow::new(path, Some(&self.commit_hash)),
+                ScreenSize::max(),
+                Position::default(),
+                screen()
+            ).render();
+        }
+
+        true
+    }
+
+    fn open_blame(&mut self, window: &mut Window) -> bool {
+        if let Some(Some(path)) = self.entries.get(window.cursor_index()) {
+            Renderer::new(
+                &mut BlameWindow::new(path, Some(&self.commit_hash)),
+                ScreenSize::max(),
+                Position::default(),
+                screen()
+            ).render();
+        }
+
+        true
+    }
+
+    fn open_file(&mut self, window: &mut Window) -> bool {
+        if let Some(Some(path)) = self.entries.get(window.cursor_index()) {
+            Renderer::new(
+                &mut FileRevisionWindow::new(&self.commit_hash, path, None),
+                ScreenSize::max(),
+                Position::default(),
+                screen()
+            ).render();
+        }
+
+        true
+    }
+
+    fn open_full_diff(&mut self, _window: &mut Window) -> bool {
+        self.open_diff(None);
+
+        true
+    }
+
+    fn next_parent(&mut self, window: &mut Window) -> bool {
+        let parents = self.details.as_ref().map_or(0, |d| d.parents.len());
+
+        if parents > 1 {
+            self.parent_index = (self.parent_index + 1) % parents;
+            self.on_start(window);
+        }
+
+        true
+    }
+
+    fn metadata_lines(details: &CommitDetails) -> Vec<Line> {
+        let label = |name: &str| Part::new(&format!("{:<11}", name), Some(vec![Style::Bold]));
+
+        let parents = if details.parents.is_empty() {
+            "none (root commit)".to_owned()
+        } else {
+            details.parents.join(" ")
+        };
+
+        let signature_color = match details.signature {
+            'G' => (0, 255, 0),
+            'B' | 'E' => (255, 0, 0),
+            'N' => (255, 255, 255),
+            _ => (255, 255, 0)
+        };
+
+        let mut signature = details.signature_description().to_owned();
+        if !details.signer.is_empty() {
+            signature.push_str(&format!(" ({})", details.signer));
+        }
+
+        let mut lines = vec![
+            Line::new(vec![label("Commit:"), Part::painted(&details.hash, (255, 255, 0), (0, 0, 0))]),
+            Line::new(vec![label("Parents:"), Part::plain(&parents)]),
+            Line::new(vec![
+                label("Author:"),
+                Part::plain(&details.author),
+                Part::plain(" "),
+                Part::painted(&details.author_date, (0, 255, 255), (0, 0, 0))
+            ]),
+            Line::new(vec![
+                label("Committer:"),
+                Part::plain(&details.committer),
+                Part::plain(" "),
+                Part::painted(&details.committer_date, (0, 255, 255), (0, 0, 0))
+            ]),
+            Line::new(vec![label("Signature:"), Part::painted(&signature, signature_color, (0, 0, 0))]),
+            Line::empty()
+        ];
+
+        // The trailers are a part of the message, they are only painted.
+        lines.extend(
+            details.message
+                .iter()
+                .map(|m| {
+                    let line = format!("    {}", m);
+
+                    if details.trailers.contains(m) {
+                        Line::new(vec![Part::painted(&line, (0, 255, 255), (0, 0, 0))])
+                    } else {
+                        Line::plain(&line)
+                    }
+                })
+        );
+
+        lines
+    }
+
+    fn file_lines(files: &[FileStat]) -> Vec<Line> {
+        let count = |c: Option<usize>, sign: &str| c.map_or("bin".to_owned(), |c| format!("{}{}", sign, c));
+
+        let added_width = files.iter().map(|f| count(f.added, "+").len()).max().unwrap_or(0);
+        let deleted_width = files.iter().map(|f| count(f.deleted, "-").len()).max().unwrap_or(0);
+
+        files
+            .iter()
+            .map(|f| {
+                Line::new(vec![
+                    Part::painted(&format!("{:>width$}", count(f.added, "+"), width = added_width), (0, 255, 0), (0, 0, 0)),
+                    Part::plain(" "),
+                    Part::painted(&format!("{:>width$}", count(f.deleted, "-"), width = deleted_width), (255, 0, 0), (0, 0, 0)),
+                    Part::plain(" "),
+                    Part::plain(&f.path)
+                ])
+            })
+            .collect()
+    }
+}
+
+impl Component<CommitDetailWindow> for CommitDetailWindow {
+    fn on_start(&mut self, window: &mut Window) {
+        self.details = git::commit_details(&self.commit_hash);
+
+        let details = match &self.details {
+            Some(details) => details,
+            None => {
+                self.entries = vec![];
+                window.set_lines(vec![Line::plain(&format!("Commit '{}' not found.", self.commit_hash))]);
+
+                return
+            }
+        };
+
+        let mut lines = CommitDetailWindow::metadata_lines(details);
+        lines.push(Line::empty());
+
+        let parent = self.parent();
+        let files = git::commit_file_stats(&self.commit_hash, parent.as_deref());
+
+        let added: usize = files.iter().filter_map(|f| f.added).sum();
+        let deleted: usize = files.iter().filter_map(|f| f.deleted).sum();
+
+        let against = match &parent {
+            Some(parent) if details.parents.len() > 1 => format!(
+                " against parent {}/{} {} (p: next parent)",
+                self.parent_index + 1,
+                details.parents.len(),
+                &parent[..7.min(parent.len())]
+            ),
+            Some(_) => "".to_owned(),
+            None => " (root commit)".to_owned()
+        };
+
+        lines.push(
+            Line::from_str(
+                &format!("Changed files{}: ({}, +{} -{}) d: full diff, h: file history, b: blame, o: open file", against, files.len(), added, deleted),
+                Some(vec![Style::Bold, Style::Underlined])
+            )
+        );
+
+        self.entries = vec![None; lines.len()];
+        self.entries.extend(files.iter().map(|f| Some(f.path.clone())));
+
+        lines.append(&mut CommitDetailWindow::file_lines(&files));
+
+        window.set_lines(lines);
+    }
+
+    fn register_handlers(&self, handlers: &mut KeyHandlers<CommitDetailWindow>) {
+        handlers.insert(KEY_LF, CommitDetailWindow::open_file_diff);
+        handlers.insert(KEY_D_LOWER, CommitDetailWindow::open_full_diff);
+        handlers.insert(KEY_P_LOWER, CommitDetailWindow::next_parent);
+        handlers.insert(KEY_H_LOWER, CommitDetailWindow::open_file_history);
+        handlers.insert(KEY_B_LOWER, CommitDetailWindow::open_blame);
+        handlers.insert(KEY_O_LOWER, CommitDetailWindow::open_file);
+
+        register_search_handlers(handlers);
+    }
+}
+
+impl SearchableComponent<CommitDetailWindow> for CommitDetailWindow {}
@@ -1,23 +1,54 @@
 use crate::git;
 use crate::gitko::diff_display::color_diff_line;
 use crate::searchable::{SearchableComponent, register_search_handlers};
-use gitko_render::{Component, KeyHandlers, Window};
+use gitko_render::{Component, KeyHandlers, Window, SearchPattern};
 
 use gitko_common::ascii_table::{KEY_J_LOWER, KEY_K_LOWER};
 
+enum DiffSource {
+    // Commit hash, the parent to compare to and the paths to limit the diff to.
+    Commit(String, Option<String>, Vec<String>),
+    Stash(String),
+    // Revision and the path of the file to compare to the working tree.
+    Worktree(String, String)
+}
+
 pub struct CommitDiffWindow {
-    commit_hash: String,
-    term: String
+    source: DiffSource,
+    // Search to jump to the first match of once rendered.
+    search: Option<SearchPattern>
 }
 
 impl CommitDiffWindow {
-    pub fn new(commit_hash: &str) -> CommitDiffWindow {
+    pub fn new(commit_hash: &str, parent: Option<&str>, paths: &[&str]) -> CommitDiffWindow {
+        CommitDiffWindow {
+            source: DiffSource::Commit(
+                commit_hash.to_owned(),
+                parent.map(str::to_owned),
+                paths.iter().map(|p| p.to_string()).collect()
+            ),
+            search: None
+        }
+    }
+
+    pub fn stash(stash: &str) -> CommitDiffWindow {
+        CommitDiffWindow {
+            source: DiffSource::Stash(stash.to_owned()),
+            search: None
+        }
+    }
+
+    pub fn worktree(revision: &str, path: &str) -> CommitDiffWindow {
         CommitDiffWindow {
-            commit_hash: commit_hash.to_owned(),
-            term: "".to_owned()
+            source: DiffSource::Worktree(revision.to_owned(), path.to_owned()),
+            search: None
         }
     }
 
+    pub fn search_for(&mut self, pattern: SearchPattern) {
+        self.search = Some(pattern);
+    }
+
     fn move_screen_up(&mut self, window: &mut Window) -> bool {
         window.move_screen_up(1);
         true
@@ -49,12 +80,26 @@ impl Component<CommitDiffWindow> for CommitDiffWindow {
     fn on_start(&mut self, window: &mut Window) {
         window.show_cursor(false);
 
+        let diff = match &self.source {
+            DiffSource::Commit(commit_hash, parent, paths) => {
+                let paths: Vec<&str> = paths.iter().map(|p| p.as_str()).collect();
+                git::diff_commit(commit_hash, parent.as_deref(), &paths)
+            },
+            DiffSource::Stash(stash) => git::stash_show(stash),
+            DiffSource::Worktree(revision, path) => git::diff_worktree(revision, path)
+        };
+
         window.set_lines(
-            git::diff_commit(&self.commit_hash)
+            diff
                 .iter()
                 .map(|l| color_diff_line(l))
                 .collect()
         );
+
+        if let Some(pattern) = self.search.take() {
+            window.set_search(Some(pattern));
+            window.move_next();
+        }
     }
 
     fn on_exit(&mut self, window: &mut Window) {
@@ -72,12 +117,4 @@ impl Component<CommitDiffWindow> for CommitDiffWindow {
     }
 }
 
-impl SearchableComponent<CommitDiffWindow> for CommitDiffWindow {
-    fn term(&self) -> String {
-        self.term.clone()
-    }
-
-    fn set_term(&mut self, term: String) {
-        self.term = term;
-    }
-}
+impl SearchableComponent<CommitDiffWindow> for CommitDiffWindow {}
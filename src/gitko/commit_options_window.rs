@@ -35,6 +35,7 @@ impl CommitOptionsWindow {
 }
 
 impl Component<CommitOptionsWindow> for CommitOptionsWindow {
+    #[allow(clippy::useless_vec)]
     fn on_start(&mut self, window: &mut Window) {
         window.set_lines(
             vec!["", "--amend"]
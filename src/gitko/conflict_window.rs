@@ -0,0 +1,194 @@
+use std::fs::{read_to_string, write};
+
+use crate::git;
+use crate::conflict;
+use crate::conflict::{Hunk, Resolution};
+use crate::gitko::output_window::show_output;
+use crate::searchable::{SearchableComponent, register_search_handlers};
+use gitko_render::{Component, KeyHandlers, Line, Window, Part, Style};
+
+use gitko_common::ascii_table::{KEY_B_LOWER, KEY_O_LOWER, KEY_O_UPPER, KEY_T_LOWER, KEY_T_UPPER, KEY_W_LOWER};
+
+// Help and an empty line are rendered above the file content.
+const HEADER_LINES: usize = 2;
+
+pub struct ConflictWindow {
+    path: String,
+    hunks: Vec<Hunk>,
+    // Why the file could not be read, e.g. it was deleted on one of the sides.
+    error: Option<String>,
+    // Index of the conflict hunk each rendered line belongs to, if any.
+    line_hunks: Vec<Option<usize>>
+}
+
+impl ConflictWindow {
+    pub fn new(path: &str) -> ConflictWindow {
+        let (hunks, error) = match read_to_string(path) {
+            Ok(content) => (conflict::parse(&content), None),
+            Err(e) => (vec![], Some(format!("Could not read '{}': {}", path, e)))
+        };
+
+        ConflictWindow {
+            path: path.to_owned(),
+            hunks,
+            error,
+            line_hunks: vec![]
+        }
+    }
+
+    fn resolve_conflict(&mut self, window: &mut Window, resolution: Resolution) -> bool {
+        let hunk_index = self.line_hunks
+            .get(window.cursor_index())
+            .cloned()
+            .flatten();
+
+        if let Some(Hunk::Conflict(conflict)) = hunk_index.and_then(|i| self.hunks.get_mut(i)) {
+            conflict.resolution = Some(resolution);
+            self.on_start(window);
+        }
+
+        true
+    }
+
+    fn take_ours(&mut self, window: &mut Window) -> bool {
+        self.resolve_conflict(window, Resolution::Ours)
+    }
+
+    fn take_theirs(&mut self, window: &mut Window) -> bool {
+        self.resolve_conflict(window, Resolution::Theirs)
+    }
+
+    fn take_both(&mut self, window: &mut Window) -> bool {
+        self.resolve_conflict(window, Resolution::Both)
+    }
+
+    fn write_file(&mut self, window: &mut Window) -> bool {
+        // Writing would replace the file with whatever could be parsed of it.
+        if self.error.is_some() || conflict::conflict_count(&self.hunks) == 0 {
+            show_output(vec![format!("Not writing '{}', there are no conflicts in it to resolve.", self.path)]);
+            return true
+        }
+
+        write(&self.path, conflict::resolve(&self.hunks)).expect("Could not write the resolved file");
+
+        // Only mark the file as resolved once there are no conflicts left in it.
+        if conflict::unresolved_count(&self.hunks) > 0 {
+            *self = ConflictWindow::new(&self.path);
+            self.on_start(window);
+            return true
+        }
+
+        show_output(git::mark_resolved(&self.path));
+
+        false
+    }
+
+    fn checkout_ours(&mut self, _window: &mut Window) -> bool {
+        show_output(git::checkout_conflict_side(&self.path, "--ours"));
+        false
+    }
+
+    fn checkout_theirs(&mut self, _window: &mut Window) -> bool {
+        show_output(git::checkout_conflict_side(&self.path, "--theirs"));
+        false
+    }
+}
+
+impl Component<ConflictWindow> for ConflictWindow {
+    fn on_start(&mut self, window: &mut Window) {
+        let mut lines = vec![
+            Line::from_str(
+                &format!(
+                    "{}: {} of {} conflicts unresolved (o/t/b: ours/theirs/both, O/T: whole file, w: write)",
+                    self.path,
+                    conflict::unresolved_count(&self.hunks),
+                    conflict::conflict_count(&self.hunks)
+                ),
+                Some(vec![Style::Bold, Style::Underlined])
+            ),
+            Line::empty()
+        ];
+        let mut line_hunks = vec![None; HEADER_LINES];
+
+        if let Some(error) = &self.error {
+            lines.push(Line::new(vec![Part::painted(error, (255, 0, 0), (0, 0, 0))]));
+            line_hunks.push(None);
+        }
+
+        for (index, hunk) in self.hunks.iter().enumerate() {
+            match hunk {
+                Hunk::Text(text) => {
+                    for l in text {
+                        lines.push(Line::plain(strip_line_ending(l)));
+                        line_hunks.push(None);
+                    }
+                },
+                Hunk::Conflict(conflict) => {
+                    let mut conflict_lines: Vec<Line> = vec![];
+
+                    if let Some(resolution) = conflict.resolution {
+                        let resolution_name = match resolution {
+                            Resolution::Ours => "ours",
+                            Resolution::Theirs => "theirs",
+                            Resolution::Both => "both"
+                        };
+
+                        conflict_lines.push(Line::new(vec![
+                            Part::painted(
+                                &format!("[resolved: {}]", resolution_name),
+                                (0, 255, 255),
+                                (0, 0, 0)
+                            )
+                        ]));
+                        conflict_lines.extend(
+                            conflict.resolved_lines()
+                                .iter()
+                                .map(|l| Line::plain(strip_line_ending(l)))
+                        );
+                    } else {
+                        let painted = |l: &String, color| {
+                            Line::new(vec![
+                                Part::painted(strip_line_ending(l), color, (0, 0, 0))
+                            ])
+                        };
+
+                        conflict_lines.push(painted(&conflict.ours_marker, (255, 255, 0)));
+                        conflict_lines.extend(conflict.ours.iter().map(|l| painted(l, (0, 255, 0))));
+
+                        if let Some(base_marker) = &conflict.base_marker {
+                            conflict_lines.push(painted(base_marker, (255, 255, 0)));
+                            conflict_lines.extend(conflict.base.iter().map(|l| Line::plain(strip_line_ending(l))));
+                        }
+
+                        conflict_lines.push(painted(&conflict.separator, (255, 255, 0)));
+                        conflict_lines.extend(conflict.theirs.iter().map(|l| painted(l, (255, 0, 0))));
+                        conflict_lines.push(painted(&conflict.theirs_marker, (255, 255, 0)));
+                    }
+
+                    line_hunks.extend(vec![Some(index); conflict_lines.len()]);
+                    lines.append(&mut conflict_lines);
+                }
+            }
+        }
+
+        self.line_hunks = line_hunks;
+        window.set_lines(lines);
+    }
+
+    fn register_handlers(&self, handlers: &mut KeyHandlers<ConflictWindow>) {
+        handlers.insert(KEY_O_LOWER, ConflictWindow::take_ours);
+        handlers.insert(KEY_T_LOWER, ConflictWindow::take_theirs);
+        handlers.insert(KEY_B_LOWER, ConflictWindow::take_both);
+        handlers.insert(KEY_W_LOWER, ConflictWindow::write_file);
+        handlers.insert(KEY_O_UPPER, ConflictWindow::checkout_ours);
+        handlers.insert(KEY_T_UPPER, ConflictWindow::checkout_theirs);
+
+        register_search_handlers(handlers);
+    }
+}
+
+impl SearchableComponent<ConflictWindow> for ConflictWindow {}
+
+fn strip_line_ending(line: &str) -> &str {
+    line.trim_end_matches(&['\r', '\n'][..])
+}
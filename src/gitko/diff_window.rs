@@ -10,16 +10,14 @@ use gitko_common::ascii_table::{KEY_J_LOWER, KEY_K_LOWER};
 
 pub struct DiffWindow {
     path: String,
-    file_state: FileState,
-    term: String
+    file_state: FileState
 }
 
 impl DiffWindow {
     pub fn new(path: &str, file_state: FileState) -> DiffWindow {
         DiffWindow {
             path: path.to_string(),
-            file_state,
-            term: "".to_owned()
+            file_state
         }
     }
 
@@ -97,12 +95,4 @@ impl Component<DiffWindow> for DiffWindow {
     }
 }
 
-impl SearchableComponent<DiffWindow> for DiffWindow {
-    fn term(&self) -> String {
-        self.term.clone()
-    }
-
-    fn set_term(&mut self, term: String) {
-        self.term = term;
-    }
-}
+impl SearchableComponent<DiffWindow> for DiffWindow {}
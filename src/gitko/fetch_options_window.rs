@@ -0,0 +1,48 @@
+use crate::git;
+use crate::gitko::output_window::show_output;
+use crate::gitko::remote_window::select_remote;
+use gitko_render::{Line, KeyHandlers, Component, Window};
+
+use gitko_common::ascii_table::{KEY_LF};
+
+pub struct FetchOptionsWindow { }
+
+impl FetchOptionsWindow {
+    fn git_fetch(&mut self, window: &mut Window) -> bool {
+        let line = window.get_cursor_line().trim().to_owned();
+        let args: Vec<&str> = line.split_whitespace().collect();
+
+        window.clear();
+
+        // --all fetches every remote, so there is nothing to choose from.
+        let remote = if args.contains(&"--all") {
+            None
+        } else {
+            match select_remote() {
+                Some(remote) => Some(remote),
+                None => return false
+            }
+        };
+
+        let args = if args.is_empty() { None } else { Some(args) };
+
+        show_output(git::fetch(args, remote.as_deref()));
+
+        false
+    }
+}
+
+impl Component<FetchOptionsWindow> for FetchOptionsWindow {
+    fn on_start(&mut self, window: &mut Window) {
+        window.set_lines(
+            ["", "--prune", "--all", "--all --prune"]
+                .iter()
+                .map(|s| Line::plain(s))
+                .collect()
+        );
+    }
+
+    fn register_handlers(&self, handlers: &mut KeyHandlers<FetchOptionsWindow>) {
+        handlers.insert(KEY_LF, FetchOptionsWindow::git_fetch);
+    }
+}
@@ -0,0 +1,139 @@
+use crate::git;
+use crate::git::Commit;
+use crate::screen;
+use crate::gitko::commit_diff_window::CommitDiffWindow;
+use crate::gitko::blame_window::BlameWindow;
+use crate::gitko::commit_detail_window::CommitDetailWindow;
+use crate::searchable::{SearchableComponent, register_search_handlers};
+use gitko_render::{Component, KeyHandlers, Line, Renderer, ScreenSize, Window, Position, Part, Style};
+
+use gitko_common::ascii_table::{KEY_B_LOWER, KEY_LF, KEY_O_LOWER};
+
+// Title and an empty line are rendered above the commits.
+const HEADER_LINES: usize = 2;
+
+pub struct FileHistoryWindow {
+    path: String,
+    // The commit to start the history from, HEAD if none.
+    revision: Option<String>,
+    // The commits which changed the file and the path it had in them.
+    history: Vec<(Commit, String)>
+}
+
+impl FileHistoryWindow {
+    pub fn new(path: &str, revision: Option<&str>) -> FileHistoryWindow {
+        FileHistoryWindow {
+            path: path.to_owned(),
+            revision: revision.map(str::to_owned),
+            history: vec![]
+        }
+    }
+
+    fn selected_index(&self, window: &Window) -> Option<usize> {
+        window
+            .cursor_index()
+            .checked_sub(HEADER_LINES)
+            .filter(|i| *i < self.history.len())
+    }
+
+    // Shows the changes of the file in the commit, as a rename
+    // if the file had a different path in the previous commit.
+    fn open_diff(&mut self, window: &mut Window) -> bool {
+        if let Some(index) = self.selected_index(window) {
+            let (commit, path) = &self.history[index];
+
+            let mut paths = vec![path.as_str()];
+            if let Some((_, previous_path)) = self.history.get(index + 1) {
+                if previous_path != path {
+                    paths.push(previous_path);
+                }
+            }
+
+            Renderer::new(
+                &mut CommitDiffWindow::new(&commit.hash, commit.parents.first().map(|p| p.as_str()), &paths),
+                ScreenSize::max(),
+                Position::default(),
+                screen()
+            ).render();
+        }
+
+        true
+    }
+
+    fn open_commit(&mut self, window: &mut Window) -> bool {
+        if let Some(index) = self.selected_index(window) {
+            Renderer::new(
+                &mut CommitDetailWindow::new(&self.history[index].0.hash),
+                ScreenSize::max(),
+                Position::default(),
+                screen()
+            ).render();
+        }
+
+        true
+    }
+
+    fn open_blame(&mut self, window: &mut Window) -> bool {
+        if let Some((commit, path)) = self.selected_index(window).map(|i| &self.history[i]) {
+            Renderer::new(
+                &mut BlameWindow::new(path, Some(&commit.hash)),
+                ScreenSize::max(),
+                Position::default(),
+                screen()
+            ).render();
+        }
+
+        true
+    }
+}
+
+impl Component<FileHistoryWindow> for FileHistoryWindow {
+    fn on_start(&mut self, window: &mut Window) {
+        self.history = git::file_history(&self.path, self.revision.as_deref());
+
+        let mut lines = vec![
+            Line::from_str(
+                &format!("History of {}: ({}) Enter: diff, o: commit, b: blame", self.path, self.history.len()),
+                Some(vec![Style::Bold, Style::Underlined])
+            ),
+            Line::empty()
+        ];
+
+        let date_width = self.history.iter().map(|(c, _)| c.relative_date.len()).max().unwrap_or(0);
+        let author_width = self.history.iter().map(|(c, _)| c.author.len()).max().unwrap_or(0);
+
+        lines.extend(
+            self.history
+                .iter()
+                .map(|(commit, path)| {
+                    let mut parts = vec![
+                        Part::painted(&commit.short_hash, (255, 255, 0), (0, 0, 0)),
+                        Part::plain(" "),
+                        Part::painted(&format!("{:<width$}", commit.relative_date, width = date_width), (0, 255, 255), (0, 0, 0)),
+                        Part::plain(" "),
+                        Part::painted(&format!("{:<width$}", commit.author, width = author_width), (0, 255, 0), (0, 0, 0)),
+                        Part::plain(" "),
+                        Part::plain(&commit.subject)
+                    ];
+
+                    if *path != self.path {
+                        parts.push(Part::painted(&format!(" [{}]", path), (255, 0, 255), (0, 0, 0)));
+                    }
+
+                    Line::new(parts)
+                })
+        );
+
+        window.set_lines(lines);
+    }
+
+    fn register_handlers(&self, handlers: &mut KeyHandlers<FileHistoryWindow>) {
+        handlers.insert(KEY_LF, FileHistoryWindow::open_diff);
+        handlers.insert(KEY_O_LOWER, FileHistoryWindow::open_commit);
+        handlers.insert(KEY_B_LOWER, FileHistoryWindow::open_blame);
+
+        register_search_handlers(handlers);
+    }
+}
+
+impl SearchableComponent<FileHistoryWindow> for FileHistoryWindow {}
@@ -0,0 +1,103 @@
+use crate::git;
+use crate::screen;
+use crate::gitko::commit_diff_window::CommitDiffWindow;
+use crate::searchable::{SearchableComponent, register_search_handlers};
+use gitko_render::{Component, KeyHandlers, Line, Renderer, ScreenSize, Window, Position, Part, Style, SearchPattern};
+
+use gitko_common::ascii_table::{KEY_D_LOWER};
+
+// Title and an empty line are rendered above the file.
+const HEADER_LINES: usize = 2;
+
+// Contents of a file as of a revision.
+pub struct FileRevisionWindow {
+    revision: String,
+    // Relative to the repository root, as git prints it for commits.
+    path: String,
+    // Line of the file to put the cursor on, starting at 1.
+    line: Option<usize>,
+    // Search to continue with n and N once rendered.
+    search: Option<SearchPattern>
+}
+
+impl FileRevisionWindow {
+    pub fn new(revision: &str, path: &str, line: Option<usize>) -> FileRevisionWindow {
+        FileRevisionWindow {
+            revision: revision.to_owned(),
+            path: path.to_owned(),
+            line,
+            search: None
+        }
+    }
+
+    pub fn search_for(&mut self, pattern: SearchPattern) {
+        self.search = Some(pattern);
+    }
+
+    fn diff_worktree(&mut self, _window: &mut Window) -> bool {
+        Renderer::new(
+            &mut CommitDiffWindow::worktree(&self.revision, &self.path),
+            ScreenSize::max(),
+            Position::default(),
+            screen()
+        ).render();
+
+        true
+    }
+}
+
+impl Component<FileRevisionWindow> for FileRevisionWindow {
+    fn on_start(&mut self, window: &mut Window) {
+        let revision = &self.revision[..8.min(self.revision.len())];
+
+        let mut lines = vec![
+            Line::from_str(
+                &format!("{} at {}: (d: diff with working tree)", self.path, revision),
+                Some(vec![Style::Bold, Style::Underlined])
+            ),
+            Line::empty()
+        ];
+
+        let contents = match git::file_at_revision(&self.revision, &self.path) {
+            Some(contents) => contents,
+            None => {
+                lines.push(Line::plain(&format!("'{}' does not exist at {}.", self.path, revision)));
+                window.set_lines(lines);
+
+                return
+            }
+        };
+
+        let number_width = contents.len().to_string().len();
+
+        lines.extend(
+            contents
+                .iter()
+                .enumerate()
+                .map(|(i, l)| {
+                    Line::new(vec![
+                        Part::painted(&format!("{:>width$} ", i + 1, width = number_width), (150, 150, 150), (0, 0, 0)),
+                        Part::plain(&l.replace('\t', "    "))
+                    ])
+                })
+        );
+
+        window.set_lines(lines);
+
+        if let Some(pattern) = self.search.take() {
+            window.set_search(Some(pattern));
+        }
+
+        if let Some(line) = self.line.take() {
+            window.move_cursor_to(HEADER_LINES + line.clamp(1, contents.len().max(1)) - 1);
+        }
+    }
+
+    fn register_handlers(&self, handlers: &mut KeyHandlers<FileRevisionWindow>) {
+        handlers.insert(KEY_D_LOWER, FileRevisionWindow::diff_worktree);
+
+        register_search_handlers(handlers);
+    }
+}
+
+impl SearchableComponent<FileRevisionWindow> for FileRevisionWindow {}
@@ -0,0 +1,265 @@
+use crate::git;
+use crate::git::{Commit, GrepMatch};
+use crate::screen;
+use crate::gitko::input_window::read_input;
+use crate::gitko::output_window::show_output;
+use crate::gitko::commit_diff_window::CommitDiffWindow;
+use crate::gitko::commit_detail_window::CommitDetailWindow;
+use crate::gitko::file_revision_window::FileRevisionWindow;
+use crate::searchable::{SearchableComponent, register_search_handlers};
+use gitko_render::{Component, KeyHandlers, Line, Renderer, ScreenSize, Window, Position, Part, Style, SearchFlags, SearchPattern};
+
+use gitko_common::ascii_table::{KEY_E_LOWER, KEY_LF, KEY_M_LOWER, KEY_O_LOWER, KEY_R_LOWER};
+
+// Title and an empty line are rendered above the results.
+const HEADER_LINES: usize = 2;
+
+#[derive(Clone, Copy)]
+enum SearchMode {
+    // git log -S, commits changing the number of occurrences of the term.
+    Added,
+    // git log -G, commits with changed lines matching the regex.
+    Changed,
+    // git grep, lines matching the regex in the files of the revision.
+    Grep
+}
+
+impl SearchMode {
+    fn next(self) -> SearchMode {
+        match self {
+            SearchMode::Added => SearchMode::Changed,
+            SearchMode::Changed => SearchMode::Grep,
+            SearchMode::Grep => SearchMode::Added
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            SearchMode::Added => "commits adding or removing",
+            SearchMode::Changed => "commits changing lines matching",
+            SearchMode::Grep => "lines matching"
+        }
+    }
+}
+
+enum SearchResult {
+    Commit(Commit),
+    Match(GrepMatch)
+}
+
+pub struct HistorySearchWindow {
+    mode: SearchMode,
+    // The term searched for in the history.
+    pattern: String,
+    // Revision to search, HEAD if none.
+    revision: Option<String>,
+    results: Vec<SearchResult>
+}
+
+impl HistorySearchWindow {
+    pub fn new(pattern: &str, revision: Option<&str>) -> HistorySearchWindow {
+        HistorySearchWindow {
+            mode: SearchMode::Added,
+            pattern: pattern.to_owned(),
+            revision: revision.map(str::to_owned),
+            results: vec![]
+        }
+    }
+
+    fn selected_result(&self, window: &Window) -> Option<&SearchResult> {
+        window
+            .cursor_index()
+            .checked_sub(HEADER_LINES)
+            .and_then(|i| self.results.get(i))
+    }
+
+    fn search(&self) -> Vec<SearchResult> {
+        let revision = self.revision.as_deref();
+
+        match self.mode {
+            SearchMode::Added | SearchMode::Changed => {
+                let regex = matches!(self.mode, SearchMode::Changed);
+
+                git::pickaxe(&self.pattern, regex, revision)
+                    .into_iter()
+                    .map(SearchResult::Commit)
+                    .collect()
+            },
+            SearchMode::Grep => {
+                let revision = revision.unwrap_or("HEAD");
+
+                // The header tells why there are no results.
+                if !git::is_single_revision(revision) { return vec![] }
+
+                git::grep(&self.pattern, revision)
+                    .into_iter()
+                    .map(SearchResult::Match)
+                    .collect()
+            }
+        }
+    }
+
+    // The pattern git matched with, case sensitive and
+    // a regex in all the modes but the -S one.
+    fn search_pattern(&self) -> Option<SearchPattern> {
+        let flags = SearchFlags {
+            regex: !matches!(self.mode, SearchMode::Added),
+            smart_case: false,
+            whole_word: false
+        };
+
+        SearchPattern::new(&self.pattern, flags).ok()
+    }
+
+    // Opens the diff of the commit or the file at the revision,
+    // with the cursor on the first match of the pattern.
+    fn open_result(&mut self, window: &mut Window) -> bool {
+        match self.selected_result(window) {
+            Some(SearchResult::Commit(commit)) => {
+                let mut diff_window = CommitDiffWindow::new(&commit.hash, commit.parents.first().map(|p| p.as_str()), &[]);
+                if let Some(pattern) = self.search_pattern() {
+                    diff_window.search_for(pattern);
+                }
+
+                Renderer::new(
+                    &mut diff_window,
+                    ScreenSize::max(),
+                    Position::default(),
+                    screen()
+                ).render();
+            },
+            Some(SearchResult::Match(grep_match)) => {
+                let revision = self.revision.as_deref().unwrap_or("HEAD");
+
+                let mut file_window = FileRevisionWindow::new(revision, &grep_match.path, Some(grep_match.line));
+                if let Some(pattern) = self.search_pattern() {
+                    file_window.search_for(pattern);
+                }
+
+                Renderer::new(
+                    &mut file_window,
+                    ScreenSize::max(),
+                    Position::default(),
+                    screen()
+                ).render();
+            },
+            None => {}
+        }
+
+        true
+    }
+
+    fn open_commit(&mut self, window: &mut Window) -> bool {
+        if let Some(SearchResult::Commit(commit)) = self.selected_result(window) {
+            Renderer::new(
+                &mut CommitDetailWindow::new(&commit.hash),
+                ScreenSize::max(),
+                Position::default(),
+                screen()
+            ).render();
+        }
+
+        true
+    }
+
+    fn next_mode(&mut self, window: &mut Window) -> bool {
+        self.mode = self.mode.next();
+        self.on_start(window);
+
+        true
+    }
+
+    fn edit_pattern(&mut self, window: &mut Window) -> bool {
+        let pattern = read_input("Enter the term to search the history for:");
+
+        if !pattern.is_empty() {
+            self.pattern = pattern;
+            self.on_start(window);
+        }
+
+        true
+    }
+
+    fn edit_revision(&mut self, window: &mut Window) -> bool {
+        let revision = read_input(
+            "Enter the revision to search (--all, a branch or a range, a single one to grep), leave empty for HEAD:"
+        );
+
+        if !revision.is_empty() && !git::is_valid_log_revision(&revision) {
+            show_output(vec![format!("'{}' is neither --all nor a revision or range.", revision)]);
+            return true
+        }
+
+        self.revision = if revision.is_empty() { None } else { Some(revision) };
+        self.on_start(window);
+
+        true
+    }
+
+    fn result_line(result: &SearchResult) -> Line {
+        match result {
+            SearchResult::Commit(commit) => Line::new(vec![
+                Part::painted(&commit.short_hash, (255, 255, 0), (0, 0, 0)),
+                Part::plain(" "),
+                Part::painted(&commit.date, (0, 255, 255), (0, 0, 0)),
+                Part::plain(" "),
+                Part::painted(&commit.author, (0, 255, 0), (0, 0, 0)),
+                Part::plain(" "),
+                Part::plain(&commit.subject)
+            ]),
+            SearchResult::Match(grep_match) => Line::new(vec![
+                Part::painted(&grep_match.path, (255, 0, 255), (0, 0, 0)),
+                Part::plain(":"),
+                Part::painted(&grep_match.line.to_string(), (150, 150, 150), (0, 0, 0)),
+                Part::plain(": "),
+                Part::plain(&grep_match.content.replace('\t', "    "))
+            ])
+        }
+    }
+}
+
+impl Component<HistorySearchWindow> for HistorySearchWindow {
+    fn on_start(&mut self, window: &mut Window) {
+        self.results = self.search();
+
+        let revision = self.revision.as_deref().unwrap_or("HEAD");
+
+        let mut lines = vec![
+            Line::new(vec![
+                Part::new("History search:", Some(vec![Style::Bold, Style::Underlined])),
+                Part::plain(&format!(" {} ", self.mode.description())),
+                Part::painted(&format!("'{}'", self.pattern), (0, 255, 255), (0, 0, 0)),
+                Part::plain(&format!(
+                    " in {} ({} results, m: mode, e: term, r: revision, o: commit)",
+                    revision,
+                    self.results.len()
+                ))
+            ]),
+            Line::empty()
+        ];
+
+        if matches!(self.mode, SearchMode::Grep) && !git::is_single_revision(revision) {
+            lines[1] = Line::new(vec![Part::painted(
+                &format!("'{}' is not a single revision, grep can only search the files of one.", revision),
+                (255, 0, 0),
+                (0, 0, 0)
+            )]);
+        }
+
+        lines.extend(self.results.iter().map(HistorySearchWindow::result_line));
+
+        window.set_lines(lines);
+    }
+
+    fn register_handlers(&self, handlers: &mut KeyHandlers<HistorySearchWindow>) {
+        handlers.insert(KEY_LF, HistorySearchWindow::open_result);
+        handlers.insert(KEY_O_LOWER, HistorySearchWindow::open_commit);
+        handlers.insert(KEY_M_LOWER, HistorySearchWindow::next_mode);
+        handlers.insert(KEY_E_LOWER, HistorySearchWindow::edit_pattern);
+        handlers.insert(KEY_R_LOWER, HistorySearchWindow::edit_revision);
+
+        register_search_handlers(handlers);
+    }
+}
+
+impl SearchableComponent<HistorySearchWindow> for HistorySearchWindow {}
@@ -1,4 +1,7 @@
-use gitko_render::{Component, Window};
+use crate::screen;
+use crate::{max_height, max_width};
+use crate::gitko::text_window::TextWindow;
+use gitko_render::{Component, Renderer, ScreenSize, Position, Window};
 
 pub struct InputWindow {
     pub text: String
@@ -19,3 +22,24 @@ impl Component<InputWindow> for InputWindow {
         false
     }
 }
+
+// Shows the message above an input line at the bottom
+// of the screen and returns whatever was typed in.
+pub fn read_input(message: &str) -> String {
+    Renderer::new(
+        &mut TextWindow { lines: vec![message] },
+        ScreenSize { lines: 1, cols: max_width() },
+        Position { x: 0, y: max_height() - 2 },
+        screen()
+    ).draw();
+
+    let mut input_window = InputWindow::new();
+    Renderer::new(
+        &mut input_window,
+        ScreenSize { lines: 1, cols: max_width() },
+        Position { x: 0, y: max_height() - 1 },
+        screen()
+    ).render();
+
+    input_window.text
+}
@@ -0,0 +1,113 @@
+use crate::git;
+use crate::git::LogFilter;
+use crate::gitko::input_window::read_input;
+use crate::gitko::output_window::show_output;
+use gitko_render::{Line, KeyHandlers, Component, Window, Part, Style};
+
+use gitko_common::ascii_table::{KEY_LF};
+
+// Title is rendered above the fields.
+const HEADER_LINES: usize = 1;
+
+const FIELDS: [&str; 8] = [
+    "Author",
+    "Since",
+    "Until",
+    "Message",
+    "Path",
+    "Revisions",
+    "First parent",
+    "No merges"
+];
+
+// Lets the user change the filter, which is read back once the window closes.
+pub struct LogFilterWindow {
+    pub filter: LogFilter
+}
+
+impl LogFilterWindow {
+    pub fn new(filter: LogFilter) -> LogFilterWindow {
+        LogFilterWindow { filter }
+    }
+
+    // Number of lines the window needs, the fields, title and the clear line.
+    pub fn height() -> i32 {
+        (FIELDS.len() + HEADER_LINES + 1) as i32
+    }
+
+    fn text_field(&mut self, index: usize) -> Option<&mut Option<String>> {
+        match index {
+            0 => Some(&mut self.filter.author),
+            1 => Some(&mut self.filter.since),
+            2 => Some(&mut self.filter.until),
+            3 => Some(&mut self.filter.grep),
+            4 => Some(&mut self.filter.path),
+            5 => Some(&mut self.filter.revision),
+            _ => None
+        }
+    }
+
+    fn edit_field(&mut self, window: &mut Window) -> bool {
+        let index = match window.cursor_index().checked_sub(HEADER_LINES) {
+            Some(index) => index,
+            None => return true
+        };
+
+        match index {
+            6 => self.filter.first_parent = !self.filter.first_parent,
+            7 => self.filter.no_merges = !self.filter.no_merges,
+            8 => self.filter = LogFilter::default(),
+            _ => {
+                let hint = match index {
+                    1 | 2 => " (e.g. 2 weeks ago or 2024-01-31)",
+                    3 => " (regex matched against commit messages)",
+                    5 => " (--all, a branch or a range like master..feature)",
+                    _ => ""
+                };
+
+                let value = read_input(&format!("Enter {}{}, leave empty to clear:", FIELDS[index].to_lowercase(), hint));
+
+                if index == 5 && !value.is_empty() && !git::is_valid_log_revision(&value) {
+                    show_output(vec![format!("'{}' is neither --all nor a revision or range.", value)]);
+                } else if let Some(field) = self.text_field(index) {
+                    *field = if value.is_empty() { None } else { Some(value) };
+                }
+            }
+        }
+
+        self.on_start(window);
+
+        true
+    }
+}
+
+impl Component<LogFilterWindow> for LogFilterWindow {
+    fn on_start(&mut self, window: &mut Window) {
+        let label_width = FIELDS.iter().map(|f| f.len()).max().unwrap_or(0) + 1;
+
+        let mut lines = vec![
+            Line::from_str("Log filter: (Enter: change, q: apply)", Some(vec![Style::Bold, Style::Underlined]))
+        ];
+
+        for (index, field) in FIELDS.iter().enumerate() {
+            let value = match index {
+                6 => (if self.filter.first_parent { "yes" } else { "no" }).to_owned(),
+                7 => (if self.filter.no_merges { "yes" } else { "no" }).to_owned(),
+                _ => self.text_field(index).and_then(|f| f.clone()).unwrap_or_default()
+            };
+
+            lines.push(Line::new(vec![
+                Part::bold(&format!("{:<width$}", format!("{}:", field), width = label_width + 1)),
+                Part::painted(&value, (0, 255, 255), (0, 0, 0))
+            ]));
+        }
+
+        lines.push(Line::plain("Clear the filter"));
+
+        window.set_lines(lines);
+    }
+
+    fn register_handlers(&self, handlers: &mut KeyHandlers<LogFilterWindow>) {
+        handlers.insert(KEY_LF, LogFilterWindow::edit_field);
+    }
+}
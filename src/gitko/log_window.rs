@@ -1,49 +1,311 @@
 use crate::git;
+use crate::git::{Commit, LogFilter, RefKind};
+use crate::graph;
+use crate::graph::GraphCell;
 use crate::screen;
 use crate::{max_height};
 use crate::gitko::output_window::OutputWindow;
-use crate::gitko::commit_diff_window::CommitDiffWindow;
+use crate::gitko::rebase_window::RebaseWindow;
+use crate::gitko::prompt_window::PromptWindow;
+use crate::gitko::output_window::show_output;
+use crate::gitko::input_window::read_input;
+use crate::gitko::commit_detail_window::CommitDetailWindow;
+use crate::gitko::log_filter_window::LogFilterWindow;
+use crate::gitko::file_revision_window::FileRevisionWindow;
+use crate::gitko::history_search_window::HistorySearchWindow;
 use crate::searchable::{SearchableComponent, register_search_handlers};
-use gitko_render::{Component, KeyHandlers, Line,Renderer, ScreenSize, Window, Position, Part};
+use gitko_render::{Component, KeyHandlers, Line,Renderer, ScreenSize, Window, Position, Part, Style};
 
-use gitko_common::ascii_table::{KEY_LF, KEY_N_LOWER, KEY_N_UPPER, KEY_R_UPPER};
+use gitko_common::ascii_table::{KEY_A_LOWER, KEY_B_LOWER, KEY_C_LOWER, KEY_D_LOWER, KEY_D_UPPER, KEY_F_LOWER,
+                                KEY_I_LOWER, KEY_LF, KEY_N_LOWER, KEY_N_UPPER, KEY_O_LOWER, KEY_R_UPPER, KEY_S_LOWER,
+                                KEY_T_LOWER, KEY_T_UPPER, KEY_V_LOWER};
+
+// The filter line is rendered above the commits.
+const HEADER_LINES: usize = 1;
+
+const MAX_AUTHOR_WIDTH: usize = 20;
+
+const LANE_COLORS: [(u8, u8, u8); 6] = [
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (100, 150, 255),
+    (255, 0, 255),
+    (0, 255, 255)
+];
 
 pub struct LogWindow {
-    term: String,
+    filter: LogFilter,
+    show_date: bool,
+    show_author: bool,
+    relative_dates: bool,
+    // The commit each rendered line shows.
+    entries: Vec<Commit>,
+    graph: Vec<Vec<GraphCell>>
 }
 
 impl LogWindow {
     pub fn new() -> LogWindow {
-        LogWindow { term: "".to_owned() }
+        LogWindow {
+            filter: LogFilter::default(),
+            show_date: true,
+            show_author: true,
+            relative_dates: true,
+            entries: vec![],
+            graph: vec![]
+        }
     }
 
-    fn get_commit_log(&mut self, window: &mut Window) -> bool {
-        let line = window.get_cursor_line();
-        let trimmed_line = line
-            .trim_matches(|c| c == '|' || c == '\\' || c == '*' || c == ' ');
+    // Short hash of the commit under the cursor.
+    fn selected_commit(&self, window: &Window) -> Option<String> {
+        let index = window.cursor_index().checked_sub(HEADER_LINES)?;
 
-        if trimmed_line.is_empty() {
-            return true;
+        self.entries
+            .get(index)
+            .map(|c| c.short_hash.clone())
+    }
+
+    fn get_commit_log(&mut self, window: &mut Window) -> bool {
+        if let Some(commit_hash) = self.selected_commit(window) {
+            Renderer::new(
+                &mut CommitDetailWindow::new(&commit_hash),
+                ScreenSize::max(),
+                Position::default(),
+                screen()
+            ).render();
         }
 
-        let commit_hash = &trimmed_line[0..7];
+        true
+    }
+
+    fn open_filter_window(&mut self, window: &mut Window) -> bool {
+        let mut filter_window = LogFilterWindow::new(self.filter.clone());
+        let filter_window_height = LogFilterWindow::height();
 
         Renderer::new(
-            &mut CommitDiffWindow::new(commit_hash),
-            ScreenSize::max(),
-            Position::default(),
+            &mut filter_window,
+            ScreenSize { lines: filter_window_height, cols: window.width() },
+            Position { x: 0, y: window.height() - filter_window_height },
             screen()
         ).render();
 
+        self.filter = filter_window.filter;
+        self.on_start(window);
+
         true
     }
 
-    fn open_reset_options(&mut self, window: &mut Window) -> bool {
-        let line = window.get_cursor_line().trim().to_owned();
+    fn toggle_date_column(&mut self, window: &mut Window) -> bool {
+        self.show_date = !self.show_date;
+        self.render_log(window);
 
-        if let Some(commit_hash) = parse_commit_hash(&line) {
+        true
+    }
+
+    fn toggle_author_column(&mut self, window: &mut Window) -> bool {
+        self.show_author = !self.show_author;
+        self.render_log(window);
+
+        true
+    }
+
+    fn toggle_relative_dates(&mut self, window: &mut Window) -> bool {
+        self.relative_dates = !self.relative_dates;
+        self.render_log(window);
+
+        true
+    }
+
+    fn open_rebase_window(&mut self, window: &mut Window) -> bool {
+        if let Some(commit_hash) = self.selected_commit(window) {
             Renderer::new(
-                &mut ResetOptionsWindow { commit_hash: commit_hash.to_owned() },
+                &mut RebaseWindow::new(&commit_hash),
+                ScreenSize::max(),
+                Position::default(),
+                screen()
+            ).render();
+        }
+
+        self.on_start(window);
+
+        true
+    }
+
+    fn git_cherry_pick(&mut self, window: &mut Window) -> bool {
+        if let Some(commit_hash) = self.selected_commit(window) {
+            let Some(mainline) = select_mainline(&commit_hash) else { return true };
+
+            Renderer::new(
+                &mut PromptWindow::new(
+                    &format!("Are you sure you want to cherry-pick commit '{}'? y/n", commit_hash),
+                    || { show_output(git::cherry_pick(&commit_hash, mainline)); },
+                    || { /* Do nothing on no. */ }
+                ),
+                ScreenSize { lines: 1, cols: 0 },
+                Position { x: 0, y: window.height() - 1 },
+                screen()
+            ).render();
+        }
+
+        self.on_start(window);
+
+        true
+    }
+
+    fn git_revert(&mut self, window: &mut Window) -> bool {
+        if let Some(commit_hash) = self.selected_commit(window) {
+            let Some(mainline) = select_mainline(&commit_hash) else { return true };
+
+            Renderer::new(
+                &mut PromptWindow::new(
+                    &format!("Are you sure you want to revert commit '{}'? y/n", commit_hash),
+                    || { show_output(git::revert(&commit_hash, mainline)); },
+                    || { /* Do nothing on no. */ }
+                ),
+                ScreenSize { lines: 1, cols: 0 },
+                Position { x: 0, y: window.height() - 1 },
+                screen()
+            ).render();
+        }
+
+        self.on_start(window);
+
+        true
+    }
+
+    fn create_branch(&mut self, window: &mut Window) -> bool {
+        if let Some(commit_hash) = self.selected_commit(window) {
+            let name = read_input(&format!("Enter name of the new branch at '{}':", commit_hash));
+
+            if !name.is_empty() {
+                show_output(git::create_branch(&name, Some(&commit_hash)));
+            }
+        }
+
+        self.on_start(window);
+
+        true
+    }
+
+    fn create_tag(&mut self, window: &mut Window) -> bool {
+        if let Some(commit_hash) = self.selected_commit(window) {
+            let name = read_input(&format!("Enter name of the tag on '{}':", commit_hash));
+
+            if !name.is_empty() {
+                show_output(git::create_tag(&name, &commit_hash, None));
+            }
+        }
+
+        self.on_start(window);
+
+        true
+    }
+
+    fn create_annotated_tag(&mut self, window: &mut Window) -> bool {
+        if let Some(commit_hash) = self.selected_commit(window) {
+            let name = read_input(&format!("Enter name of the annotated tag on '{}':", commit_hash));
+
+            if !name.is_empty() {
+                let message = read_input(&format!("Enter message of the tag '{}':", name));
+                show_output(git::create_tag(&name, &commit_hash, Some(&message)));
+            }
+        }
+
+        self.on_start(window);
+
+        true
+    }
+
+    fn open_file(&mut self, window: &mut Window) -> bool {
+        if let Some(commit_hash) = self.selected_commit(window) {
+            let path = read_input(&format!("Enter path of the file to show at '{}':", commit_hash));
+
+            if !path.is_empty() {
+                Renderer::new(
+                    &mut FileRevisionWindow::new(&commit_hash, &git::root_relative_path(&path), None),
+                    ScreenSize::max(),
+                    Position::default(),
+                    screen()
+                ).render();
+            }
+        }
+
+        true
+    }
+
+    fn open_history_search(&mut self, _window: &mut Window) -> bool {
+        let pattern = read_input("Enter the term to search the history for:");
+
+        if !pattern.is_empty() {
+            Renderer::new(
+                &mut HistorySearchWindow::new(&pattern, self.filter.revision.as_deref()),
+                ScreenSize::max(),
+                Position::default(),
+                screen()
+            ).render();
+        }
+
+        true
+    }
+
+    fn render_log(&mut self, window: &mut Window) {
+        let commits = || self.entries.iter();
+
+        let date = |c: &Commit| if self.relative_dates { c.relative_date.clone() } else { c.date.clone() };
+        let date_width = commits().map(|c| date(c).chars().count()).max().unwrap_or(0);
+        let author_width = commits()
+            .map(|c| c.author.chars().count())
+            .max()
+            .unwrap_or(0)
+            .min(MAX_AUTHOR_WIDTH);
+
+        let filter = if self.filter.is_empty() {
+            "HEAD".to_owned()
+        } else {
+            self.filter.args().join(" ")
+        };
+
+        let mut lines = vec![
+            Line::new(vec![
+                Part::new("Log:", Some(vec![Style::Bold, Style::Underlined])),
+                Part::painted(&format!(" {}", filter), (0, 255, 255), (0, 0, 0)),
+                Part::plain(&format!(" ({} commits, f: filter, s: search history, o: open file, a/d/D: author/date columns)", self.entries.len()))
+            ])
+        ];
+
+        lines.extend(self.entries
+            .iter()
+            .zip(&self.graph)
+            .map(|(commit, graph)| {
+                let mut parts = graph_parts(graph);
+                parts.push(Part::plain(" "));
+
+                parts.push(Part::painted(&commit.short_hash, (255, 255, 0), (0, 0, 0)));
+                parts.push(Part::plain(" "));
+
+                if self.show_date {
+                    parts.push(Part::painted(&pad(&date(commit), date_width), (0, 255, 255), (0, 0, 0)));
+                    parts.push(Part::plain(" "));
+                }
+
+                if self.show_author {
+                    parts.push(Part::painted(&pad(&commit.author, author_width), (0, 255, 0), (0, 0, 0)));
+                    parts.push(Part::plain(" "));
+                }
+
+                parts.append(&mut ref_parts(&commit.refs));
+                parts.push(Part::plain(&commit.subject));
+
+                Line::new(truncate_parts(parts, window.width() as usize))
+            }));
+
+        window.set_lines(lines);
+    }
+
+    fn open_reset_options(&mut self, window: &mut Window) -> bool {
+        if let Some(commit_hash) = self.selected_commit(window) {
+            Renderer::new(
+                &mut ResetOptionsWindow { commit_hash },
                 ScreenSize { lines: 5, cols: window.width() },
                 Position { x: 0, y: window.height() - 5 },
                 screen()
@@ -56,45 +318,89 @@ impl LogWindow {
     }
 }
 
-fn map_line(line: &str) -> Line {
+// Paints the refs the way git log --decorate shows them,
+// e.g. " (HEAD -> master, origin/master, tag: v1.0)".
+fn ref_parts(refs: &[(RefKind, String)]) -> Vec<Part> {
+    if refs.is_empty() { return vec![] }
+
+    let mut parts = vec![Part::plain("(")];
+
+    for (i, (kind, name)) in refs.iter().enumerate() {
+        if i > 0 {
+            parts.push(Part::plain(", "));
+        }
+
+        let head_style = vec![Style::Bold, Style::Painted((0, 255, 255), (0, 0, 0))];
+
+        let part = match kind {
+            RefKind::Head => Part::new(name, Some(head_style)),
+            RefKind::CurrentBranch => {
+                parts.push(Part::new("HEAD -> ", Some(head_style)));
+                Part::new(name, Some(vec![Style::Bold, Style::Painted((0, 255, 0), (0, 0, 0))]))
+            },
+            RefKind::Local => Part::painted(name, (0, 255, 0), (0, 0, 0)),
+            RefKind::Remote => Part::painted(name, (255, 0, 0), (0, 0, 0)),
+            RefKind::Tag => Part::new(&format!("tag: {}", name), Some(vec![Style::Bold, Style::Painted((255, 0, 255), (0, 0, 0))]))
+        };
+
+        parts.push(part);
+    }
+
+    parts.push(Part::plain(") "));
+
+    parts
+}
+
+// Joins the cells of the same color into parts.
+fn graph_parts(cells: &[GraphCell]) -> Vec<Part> {
     let mut parts: Vec<Part> = vec![];
+    let mut previous_color = None;
+
+    for cell in cells {
+        match parts.last_mut() {
+            Some(part) if previous_color == Some(cell.color) => part.value.push(cell.symbol),
+            _ => {
+                let color = LANE_COLORS[cell.color % LANE_COLORS.len()];
+                parts.push(Part::painted(&cell.symbol.to_string(), color, (0, 0, 0)));
+                previous_color = Some(cell.color);
+            }
+        }
+    }
 
-    let mut chars = line.chars();
-    let star = chars.position(|c| c == '*');
-    if let Some(star_position) = star {
-        let start = chars.position(|c| c != ' ');
+    parts
+}
 
-        if let Some(start_position) = start {
-            let hash_start = star_position + start_position + 1;
+// Cuts the parts so that the line fits into the width.
+fn truncate_parts(parts: Vec<Part>, width: usize) -> Vec<Part> {
+    let mut remaining = width;
+    let mut truncated = vec![];
 
-            parts.push(Part::plain(&line[0..hash_start]));
+    for mut part in parts {
+        if remaining == 0 { break }
 
-            let hash_length = 7;
-            parts.push(
-                Part::painted(
-                    &line[hash_start..hash_start + hash_length],
-                    (255, 255, 0),
-                    (0, 0, 0)
-                )
-            );
+        let length = part.value.chars().count();
 
-            parts.push(Part::plain(&line[hash_start + hash_length..]));
+        if length > remaining {
+            part.value = part.value.chars().take(remaining).collect();
         }
-    } else {
-        parts.push(Part::plain(line));
+
+        remaining -= length.min(remaining);
+        truncated.push(part);
     }
 
-    Line::new(parts)
+    truncated
+}
+
+fn pad(value: &str, width: usize) -> String {
+    let truncated: String = value.chars().take(width).collect();
+    format!("{:<width$}", truncated, width = width)
 }
 
 impl Component<LogWindow> for LogWindow {
     fn on_start(&mut self, window: &mut Window) {
-        window.set_lines(
-            git::log(None)
-                .iter()
-                .map(|l| map_line(l))
-                .collect()
-        );
+        self.entries = git::log(&self.filter, None);
+        self.graph = graph::layout(&self.entries);
+        self.render_log(window);
     }
 
     fn register_handlers(&self, handlers: &mut KeyHandlers<LogWindow>) {
@@ -102,21 +408,49 @@ impl Component<LogWindow> for LogWindow {
         handlers.insert(KEY_N_LOWER, LogWindow::next_search_result);
         handlers.insert(KEY_N_UPPER, LogWindow::prev_search_result);
         handlers.insert(KEY_R_UPPER, LogWindow::open_reset_options);
+        handlers.insert(KEY_I_LOWER, LogWindow::open_rebase_window);
+        handlers.insert(KEY_C_LOWER, LogWindow::git_cherry_pick);
+        handlers.insert(KEY_V_LOWER, LogWindow::git_revert);
+        handlers.insert(KEY_T_LOWER, LogWindow::create_tag);
+        handlers.insert(KEY_B_LOWER, LogWindow::create_branch);
+        handlers.insert(KEY_T_UPPER, LogWindow::create_annotated_tag);
+        handlers.insert(KEY_D_LOWER, LogWindow::toggle_date_column);
+        handlers.insert(KEY_D_UPPER, LogWindow::toggle_relative_dates);
+        handlers.insert(KEY_A_LOWER, LogWindow::toggle_author_column);
+        handlers.insert(KEY_F_LOWER, LogWindow::open_filter_window);
+        handlers.insert(KEY_O_LOWER, LogWindow::open_file);
+        handlers.insert(KEY_S_LOWER, LogWindow::open_history_search);
         register_search_handlers(handlers);
     }
 }
 
-impl SearchableComponent<LogWindow> for LogWindow {
-    fn term(&self) -> String {
-        self.term.clone()
-    }
+impl SearchableComponent<LogWindow> for LogWindow {}
+
+
+// Asks which parent of a merge commit to cherry-pick or revert it relative to.
+// Some(None) for other commits, None if no valid parent number was entered.
+fn select_mainline(commit_hash: &str) -> Option<Option<usize>> {
+    let parents = git::commit_details(commit_hash).map_or(0, |d| d.parents.len());
+    if parents < 2 { return Some(None) }
 
-    fn set_term(&mut self, term: String) {
-        self.term = term;
+    let input = read_input(&format!(
+        "'{}' is a merge, enter the number of the parent to use as mainline (1-{}):",
+        commit_hash,
+        parents
+    ));
+
+    match input.trim().parse::<usize>() {
+        Ok(mainline) if (1..=parents).contains(&mainline) => Some(Some(mainline)),
+        _ => {
+            if !input.is_empty() {
+                show_output(vec![format!("'{}' is not a parent number between 1 and {}.", input, parents)]);
+            }
+
+            None
+        }
     }
 }
 
-
 struct ResetOptionsWindow {
     commit_hash: String
 }
@@ -144,6 +478,7 @@ impl ResetOptionsWindow {
 }
 
 impl Component<ResetOptionsWindow> for ResetOptionsWindow {
+    #[allow(clippy::useless_vec)]
     fn on_start(&mut self, window: &mut Window) {
         window.set_lines(
             vec!["Git reset modes:", "--soft", "--mixed", "--hard", "--merge", "--keep"]
@@ -157,21 +492,3 @@ impl Component<ResetOptionsWindow> for ResetOptionsWindow {
         handlers.insert(KEY_LF, ResetOptionsWindow::git_reset);
     }
 }
-
-fn parse_commit_hash(line: &str) -> Option<&str> {
-    let mut chars = line.chars();
-
-    let star = chars.position(|c| c == '*');
-    if let Some(star_position) = star {
-        let start = chars.position(|c| c != ' ');
-
-        if let Some(start_position) = start {
-            let hash_start = star_position + start_position + 1;
-            let hash_length = 7;
-
-            return Some(&line[hash_start..hash_start + hash_length])
-        }
-    }
-
-    None
-}
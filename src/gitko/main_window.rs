@@ -1,40 +1,334 @@
-use std::fs::{metadata,read_dir,remove_file};
-use std::path::{Path, PathBuf};
+use std::collections::HashSet;
+use std::fs::{remove_dir_all, remove_file};
+use std::path::Path;
+use std::path::PathBuf;
 use std::process::Command;
+use std::thread;
 
 use crate::git;
 use crate::screen;
 use crate::git::{parse_file_state, FileState};
 use crate::gitko::log_window::LogWindow;
 use crate::gitko::diff_window::DiffWindow;
+use crate::gitko::blame_window::BlameWindow;
+use crate::gitko::file_history_window::FileHistoryWindow;
+use crate::gitko::tag_window::TagWindow;
+use crate::gitko::stash_window::StashWindow;
 use crate::gitko::branch_window::BranchWindow;
+use crate::gitko::input_window::read_input;
+use crate::gitko::output_window::show_output;
 use crate::gitko::command_window::CommandWindow;
-use crate::gitko::prompt_window::PromptWindow;
+use crate::gitko::conflict_window::ConflictWindow;
+use crate::gitko::prompt_window::confirm;
+use crate::gitko::operation_window::OperationWindow;
 use crate::gitko::push_options_window::PushOptionsWindow;
+use crate::gitko::pull_options_window::PullOptionsWindow;
+use crate::gitko::fetch_options_window::FetchOptionsWindow;
 use crate::gitko::commit_options_window::CommitOptionsWindow;
 use crate::searchable::{SearchableComponent, register_search_handlers};
+use crate::selectable::{SelectableComponent, register_selection_handlers};
 use gitko_render::{Line, Renderer, KeyHandlers, Component, ScreenSize, Window, Position, Part, Style};
 
-use gitko_common::ascii_table::{KEY_B_LOWER, KEY_COLON, KEY_C_LOWER, KEY_C_UPPER, KEY_D_LOWER, KEY_LF,
-                                KEY_L_LOWER, KEY_O_UPPER, KEY_P_UPPER, KEY_R_UPPER, KEY_T_LOWER, KEY_U_LOWER};
+use gitko_common::ascii_table::{KEY_A_UPPER, KEY_B_LOWER, KEY_B_UPPER, KEY_COLON, KEY_C_LOWER, KEY_C_UPPER, KEY_D_LOWER,
+                                KEY_F_LOWER, KEY_F_UPPER, KEY_HT, KEY_H_LOWER, KEY_LF, KEY_L_LOWER, KEY_O_UPPER,
+                                KEY_P_UPPER, KEY_R_LOWER, KEY_R_UPPER, KEY_T_LOWER, KEY_T_UPPER, KEY_U_LOWER,
+                                KEY_V_UPPER, KEY_Z_LOWER, KEY_Z_UPPER};
+
+const UNPUSHED_SECTION: &str = "Unpushed commits";
+const UNPULLED_SECTION: &str = "Unpulled commits";
+const CONFLICTS_SECTION: &str = "Conflicts";
+const UNTRACKED_SECTION: &str = "Untracked files";
+const UNTRACKED_MODIFIED_SECTION: &str = "Untracked (modified) files";
+const DELETED_SECTION: &str = "Deleted files";
+const MODIFIED_SECTION: &str = "Modified files";
+const STAGED_SECTION: &str = "Staged files";
+
+// Files listed by name in the confirmation of a destructive action.
+const MAX_DESCRIBED_FILES: usize = 5;
+
+struct StatusSection {
+    title: &'static str,
+    // Status lines of the files, e.g. " M src/main.rs".
+    files: Vec<String>
+}
+
+enum Entry {
+    Section(&'static str),
+    // Title of the section and path of the directory, e.g. "src/gitko/".
+    Directory(&'static str, String),
+    File(String)
+}
 
 pub struct MainWindow {
-    term: String
+    collapsed: HashSet<&'static str>,
+    tree_view: bool,
+    // Folded directories of the tree view, by section title.
+    folded: HashSet<(&'static str, String)>,
+    sections: Vec<StatusSection>,
+    // What each rendered line shows, if anything actionable.
+    entries: Vec<Option<Entry>>
 }
 
 impl MainWindow {
     pub fn new() -> MainWindow {
-        MainWindow { term: "".to_owned() }
+        MainWindow {
+            collapsed: HashSet::new(),
+            tree_view: false,
+            folded: HashSet::new(),
+            sections: vec![],
+            entries: vec![]
+        }
+    }
+
+    fn header(&self, upstream: Option<&str>) -> Vec<Line> {
+        let mut header = vec![
+            Line::new(vec![
+                Part::new("Head:", Some(vec![Style::Bold, Style::Underlined])),
+                Part::painted(
+                    &git::head_branch(),
+                    (0, 255, 255),
+                    (0, 0, 0)
+                ),
+                Part::plain(" "),
+                Part::plain(&git::last_commit())
+            ])
+        ];
+
+        if let Some(operation) = git::operation_in_progress() {
+            header.push(
+                Line::new(vec![
+                    Part::new(&format!("{}:", operation.name()), Some(vec![Style::Bold, Style::Underlined])),
+                    Part::painted(
+                        &format!(" {}", operation.description()),
+                        (255, 255, 0),
+                        (0, 0, 0)
+                    ),
+                    Part::plain(&format!(" r: {}", operation.actions().join(" ")))
+                ])
+            );
+        }
+
+        let upstream = match upstream {
+            Some(upstream) => upstream,
+            None => {
+                let reason = if git::is_detached_head() { " none (detached HEAD)" } else { " none configured" };
+
+                header.push(
+                    Line::new(vec![
+                        Part::new("Upstream:", Some(vec![Style::Bold, Style::Underlined])),
+                        Part::plain(reason)
+                    ])
+                );
+
+                return header
+            }
+        };
+
+        let mut upstream_line = vec![
+            Part::new("Upstream:", Some(vec![Style::Bold, Style::Underlined])),
+            Part::painted(
+                &format!(" {}", upstream),
+                (255, 0, 0),
+                (0, 0, 0)
+            ),
+            Part::plain(" "),
+            Part::plain(&git::last_upstream_commit(upstream))
+        ];
+
+        let (ahead, behind) = git::ahead_behind(upstream);
+
+        if ahead == 0 && behind == 0 {
+            upstream_line.push(Part::plain(" [up to date]"));
+        } else {
+            upstream_line.push(Part::plain(" ["));
+
+            if ahead > 0 {
+                upstream_line.push(Part::painted(&format!("ahead {}", ahead), (0, 255, 0), (0, 0, 0)));
+            }
+
+            if ahead > 0 && behind > 0 {
+                upstream_line.push(Part::plain(", "));
+            }
+
+            if behind > 0 {
+                upstream_line.push(Part::painted(&format!("behind {}", behind), (255, 0, 0), (0, 0, 0)));
+            }
+
+            upstream_line.push(Part::plain("]"));
+        }
+
+        header.push(Line::new(upstream_line));
+
+        header
+    }
+
+    fn section_title(&self, title: &'static str, count: usize) -> (Line, Option<Entry>) {
+        let collapsed = if self.collapsed.contains(title) { " ..." } else { "" };
+
+        (
+            Line::from_str(
+                &format!("{}: ({}){}", title, count, collapsed),
+                Some(vec![Style::Bold, Style::Underlined])
+            ),
+            Some(Entry::Section(title))
+        )
+    }
+
+    fn commit_section(&self, title: &'static str, commits: Vec<String>) -> Vec<(Line, Option<Entry>)> {
+        if commits.is_empty() { return vec![] }
+
+        let mut section = vec![
+            (Line::empty(), None),
+            self.section_title(title, commits.len())
+        ];
+
+        if !self.collapsed.contains(title) {
+            section.extend(commits.iter().map(|c| (Line::plain(c), None)));
+        }
+
+        section
+    }
+
+    fn file_section(&self, section: &StatusSection) -> Vec<(Line, Option<Entry>)> {
+        let mut lines = vec![self.section_title(section.title, section.files.len())];
+
+        if self.collapsed.contains(section.title) {
+            // Nothing but the title is shown.
+        } else if self.tree_view {
+            lines.append(&mut self.file_tree(section));
+        } else {
+            lines.extend(
+                section.files
+                    .iter()
+                    .map(|f| (Line::plain(f), Some(Entry::File(f.clone()))))
+            );
+        }
+
+        lines.push((Line::empty(), None));
+
+        lines
+    }
+
+    // Files of the section grouped under their directories, e.g.
+    // src/
+    //   gitko/
+    //      M main_window.rs
+    //    M git.rs
+    fn file_tree(&self, section: &StatusSection) -> Vec<(Line, Option<Entry>)> {
+        let mut files: Vec<&String> = section.files.iter().collect();
+        files.sort_by_key(|f| tree_path(f));
+
+        let mut lines = vec![];
+        let mut previous: Vec<&str> = vec![];
+
+        for file in files {
+            let mut components: Vec<&str> = tree_path(file).split('/').collect();
+            let name = components.pop().unwrap_or_default();
+
+            let common = previous
+                .iter()
+                .zip(&components)
+                .take_while(|(a, b)| a == b)
+                .count();
+
+            for depth in common..components.len() {
+                if self.is_folded(section.title, &components[..depth]) { break }
+
+                let directory = format!("{}/", components[..=depth].join("/"));
+                let folded = self.folded.contains(&(section.title, directory.clone()));
+
+                lines.push((
+                    Line::new(vec![
+                        Part::plain(&"  ".repeat(depth)),
+                        Part::painted(&format!("{}/", components[depth]), (0, 255, 255), (0, 0, 0)),
+                        Part::plain(if folded { " ..." } else { "" })
+                    ]),
+                    Some(Entry::Directory(section.title, directory))
+                ));
+            }
+
+            if !self.is_folded(section.title, &components) {
+                lines.push((
+                    Line::plain(&format!("{}{} {}", "  ".repeat(components.len()), &file[..2], name)),
+                    Some(Entry::File(file.clone()))
+                ));
+            }
+
+            previous = components;
+        }
+
+        lines
+    }
+
+    // Whether any of the directories on the path is folded.
+    fn is_folded(&self, title: &'static str, components: &[&str]) -> bool {
+        (1..=components.len())
+            .any(|i| self.folded.contains(&(title, format!("{}/", components[..i].join("/")))))
+    }
+
+    fn toggle_tree_view(&mut self, window: &mut Window) -> bool {
+        self.tree_view = !self.tree_view;
+
+        self.on_start(window);
+
+        true
+    }
+
+    // Status line of the file under the cursor.
+    fn cursor_file(&self, window: &Window) -> Option<String> {
+        match self.entries.get(window.cursor_index()) {
+            Some(Some(Entry::File(file))) => Some(file.clone()),
+            _ => None
+        }
+    }
+
+    fn section_files(&self, title: &str) -> Vec<String> {
+        self.sections
+            .iter()
+            .find(|s| s.title == title)
+            .map(|s| s.files.clone())
+            .unwrap_or_default()
+    }
+
+    fn toggle_section(&mut self, window: &mut Window) -> bool {
+        match self.entries.get(window.cursor_index()) {
+            Some(Some(Entry::Section(title))) if !self.collapsed.remove(title) => {
+                self.collapsed.insert(title);
+            },
+            Some(Some(Entry::Directory(title, directory))) => {
+                let key = (*title, directory.clone());
+
+                if !self.folded.remove(&key) {
+                    self.folded.insert(key);
+                }
+            },
+            _ => {}
+        }
+
+        self.on_start(window);
+
+        true
     }
 
     fn diff_file(&mut self, window: &mut Window) -> bool {
-        let line = window.get_cursor_line();
-        if line.is_empty() { return true }
-        if line.len() < 3 { return true }
+        let line = match self.entries.get(window.cursor_index()) {
+            Some(Some(Entry::File(file))) => file.clone(),
+            Some(Some(_)) => return self.toggle_section(window),
+            _ => return true
+        };
 
         let file_state = parse_file_state(&line);
 
-        if !matches!(file_state, FileState::Unknown) {
+        if matches!(file_state, FileState::Conflicted) {
+            Renderer::new(
+                &mut ConflictWindow::new(line[3..].trim()),
+                ScreenSize::max(),
+                Position::default(),
+                screen()
+            ).render();
+
+            self.on_start(window);
+        } else if !matches!(file_state, FileState::Unknown) {
             let path = line[3..].trim();
 
             Renderer::new(
@@ -50,7 +344,7 @@ impl MainWindow {
 
     fn open_branch_window(&mut self, window: &mut Window) -> bool {
         Renderer::new(
-            &mut BranchWindow{},
+            &mut BranchWindow::new(),
             ScreenSize::max(),
             Position::default(),
             screen()
@@ -74,6 +368,84 @@ impl MainWindow {
         true
     }
 
+    fn open_file_history(&mut self, window: &mut Window) -> bool {
+        if let Some(file) = self.cursor_file(window) {
+            Renderer::new(
+                &mut FileHistoryWindow::new(tree_path(&file), None),
+                ScreenSize::max(),
+                Position::default(),
+                screen()
+            ).render();
+        }
+
+        true
+    }
+
+    fn open_blame(&mut self, window: &mut Window) -> bool {
+        if let Some(file) = self.cursor_file(window) {
+            Renderer::new(
+                &mut BlameWindow::new(tree_path(&file), None),
+                ScreenSize::max(),
+                Position::default(),
+                screen()
+            ).render();
+        }
+
+        true
+    }
+
+    fn open_stash_window(&mut self, window: &mut Window) -> bool {
+        Renderer::new(
+            &mut StashWindow{},
+            ScreenSize::max(),
+            Position::default(),
+            screen()
+        ).render();
+
+        self.on_start(window);
+
+        true
+    }
+
+    fn open_tag_window(&mut self, window: &mut Window) -> bool {
+        Renderer::new(
+            &mut TagWindow::new(),
+            ScreenSize::max(),
+            Position::default(),
+            screen()
+        ).render();
+
+        self.on_start(window);
+
+        true
+    }
+
+    fn git_stash_file(&mut self, window: &mut Window) -> bool {
+        let line = match self.cursor_file(window) {
+            Some(line) => line,
+            None => return true
+        };
+
+        let file_state = parse_file_state(&line);
+        if matches!(file_state, FileState::Unknown) { return true }
+
+        let path = line[3..].trim();
+        let args = if matches!(file_state, FileState::Untracked) {
+            Some(vec!["--include-untracked"])
+        } else {
+            None
+        };
+
+        let message = read_input(&format!("Enter stash message for '{}' (leave empty for the default):", path));
+        let message = if message.is_empty() { None } else { Some(message.as_str()) };
+
+        show_output(git::stash_push(args, message, Some(vec![path])));
+
+        self.on_start(window);
+
+        true
+    }
+
     fn open_command_window(&mut self, window: &mut Window) -> bool {
         Renderer::new(
             &mut CommandWindow{},
@@ -96,42 +468,90 @@ impl MainWindow {
             panic!("Platform not supported.");
         };
 
-        let path_str = window.get_cursor_line()[3..].trim().to_owned();
-        if !Path::new(&path_str).exists() {
+        let mut path = match self.entries.get(window.cursor_index()) {
+            Some(Some(Entry::File(file))) => {
+                let mut path = PathBuf::from(tree_path(file));
+                path.pop();
+                path
+            },
+            Some(Some(Entry::Directory(_, directory))) => PathBuf::from(directory),
+            _ => return true
+        };
+
+        if !path.as_os_str().is_empty() && !path.exists() {
             return true
         }
 
-        let mut path = PathBuf::from(window.get_cursor_line()[3..].trim());
-        path.pop();
-
         if path.as_os_str().is_empty() {
             path.push(".");
         }
 
-        Command::new(command)
+        let mut file_manager = Command::new(command)
             .arg(path)
             .spawn()
             .unwrap();
 
+        // Reaped in the background, the file manager runs alongside gitko.
+        thread::spawn(move || file_manager.wait());
+
+        true
+    }
+
+    // Status lines of the selected files whose state matches, a
+    // selected section title or directory selects all of its files.
+    fn selected_files(&self, window: &Window, matches: fn(&str) -> bool) -> Vec<String> {
+        let mut files: Vec<String> = vec![];
+
+        for index in self.selection(window) {
+            match self.entries.get(index) {
+                Some(Some(Entry::Section(title))) => files.extend(self.section_files(title)),
+                Some(Some(Entry::Directory(title, directory))) => {
+                    files.extend(
+                        self.section_files(title)
+                            .into_iter()
+                            .filter(|f| tree_path(f).starts_with(directory.as_str()))
+                    );
+                },
+                Some(Some(Entry::File(file))) => files.push(file.clone()),
+                _ => {}
+            }
+        }
+
+        let mut seen = HashSet::new();
+        files.retain(|f| matches(f) && seen.insert(f.clone()));
+
+        files
+    }
+
+    fn git_add_all(&mut self, window: &mut Window) -> bool {
+        git::add_all();
+
+        self.on_start(window);
+
         true
     }
 
     fn delete_untracked_file(&mut self, window: &mut Window) -> bool {
-        let line = window.get_cursor_line();
-        if line.is_empty() { return true }
+        let files = self.selected_files(window, |l| matches!(parse_file_state(l), FileState::Untracked));
+        let paths: Vec<&str> = files.iter().map(|f| f[3..].trim()).collect();
+
+        if !paths.is_empty()
+            && confirm(&format!("Are you sure you want to delete {}? y/n", describe_files(&paths))) {
+            // A nested repository is listed as its directory, e.g. "dir/".
+            let errors: Vec<String> = paths
+                .iter()
+                .filter_map(|path| {
+                    let result = if path.ends_with('/') || Path::new(path).is_dir() {
+                        remove_dir_all(path)
+                    } else {
+                        remove_file(path)
+                    };
 
-        let file_state = parse_file_state(&line);
+                    result.err().map(|e| format!("Could not delete '{}': {}", path, e))
+                })
+                .collect();
 
-        if matches!(file_state, FileState::Untracked) {
-            let file = line[3..].trim();
-            Renderer::new(
-                &mut PromptWindow::new(&format!("Are you sure you want to delete file '{}'? y/n", file),
-                                  || { remove_file(file).unwrap(); },
-                                  || {}),
-                ScreenSize { lines: 1, cols: 0 },
-                Position { x: 0, y: window.height() - 1 },
-                screen()
-            ).render();
+            show_output(errors);
         }
 
         self.on_start(window);
@@ -140,21 +560,12 @@ impl MainWindow {
     }
 
     fn git_checkout_file(&mut self, window: &mut Window) -> bool {
-        let line = window.get_cursor_line();
-        if line.is_empty() { return true }
+        let files = self.selected_files(window, |l| matches!(parse_file_state(l), FileState::Modified));
+        let paths: Vec<&str> = files.iter().map(|f| f[3..].trim()).collect();
 
-        let file_state = parse_file_state(&line);
-
-        if matches!(file_state, FileState::Modified) {
-            let file = line[3..].trim();
-            Renderer::new(
-                &mut PromptWindow::new(&format!("Are you sure you want to checkout file '{}'? y/n", file),
-                                  || { git::checkout_file(file); },
-                                  || {}),
-                ScreenSize { lines: 1, cols: 0 },
-                Position { x: 0, y: window.height() - 1 },
-                screen()
-            ).render();
+        if !paths.is_empty()
+            && confirm(&format!("Are you sure you want to checkout {}? y/n", describe_files(&paths))) {
+            git::checkout_files(&paths);
         }
 
         self.on_start(window);
@@ -163,13 +574,11 @@ impl MainWindow {
     }
 
     fn git_add_file(&mut self, window: &mut Window) -> bool {
-        // TODO: add parse git status that returns file state
-        // and file path?
-        let line = window.get_cursor_line();
-        if line.is_empty() { return true }
+        let files = self.selected_files(window, git::is_file_modified);
+        let paths: Vec<&str> = files.iter().map(|f| f[3..].trim()).collect();
 
-        if git::is_file_modified(&line) {
-            git::add_file(line[3..].trim());
+        if !paths.is_empty() {
+            git::add_files(&paths);
         }
 
         self.on_start(window);
@@ -178,11 +587,11 @@ impl MainWindow {
     }
 
     fn git_unstage_file(&mut self, window: &mut Window) -> bool {
-        let line = window.get_cursor_line();
-        if line.is_empty() { return true }
+        let files = self.selected_files(window, git::is_in_worktree);
+        let paths: Vec<&str> = files.iter().map(|f| f[3..].trim()).collect();
 
-        if git::is_in_worktree(&line) {
-            git::unstage_file(line[3..].trim());
+        if !paths.is_empty() {
+            git::unstage_files(&paths);
         }
 
         self.on_start(window);
@@ -205,8 +614,8 @@ impl MainWindow {
     fn git_push_options(&mut self, window: &mut Window) -> bool {
         Renderer::new(
             &mut PushOptionsWindow{},
-            ScreenSize { lines: 2, cols: window.width() },
-            Position { x: 0, y: window.height() - 2 },
+            ScreenSize { lines: 4, cols: window.width() },
+            Position { x: 0, y: window.height() - 4 },
             screen()
         ).render();
 
@@ -214,204 +623,216 @@ impl MainWindow {
         true
     }
 
-    fn refresh(&mut self, window: &mut Window) -> bool {
+    fn git_fetch_options(&mut self, window: &mut Window) -> bool {
+        Renderer::new(
+            &mut FetchOptionsWindow{},
+            ScreenSize { lines: 4, cols: window.width() },
+            Position { x: 0, y: window.height() - 4 },
+            screen()
+        ).render();
+
         self.on_start(window);
         true
     }
-}
-
-impl Component<MainWindow> for MainWindow {
-    fn on_start(&mut self, window: &mut Window) {
-        let git_status: Vec<String> = git::status();
-
-        let untracked: Vec<String> = git_status
-            .iter()
-            .filter(|c| c.starts_with("??") || c.starts_with("AM"))
-            .cloned()
-            .collect();
-
-        let mut added: Vec<Line> = vec![];
-        let mut added_modified: Vec<Line> = vec![];
-
-        for u in &untracked {
-            let untracked_path = &u[3..];
-            let path_metadata = metadata(untracked_path);
 
-            let modified = u.chars().nth(1).unwrap() == 'M';
+    fn git_pull_options(&mut self, window: &mut Window) -> bool {
+        Renderer::new(
+            &mut PullOptionsWindow{},
+            ScreenSize { lines: 5, cols: window.width() },
+            Position { x: 0, y: window.height() - 5 },
+            screen()
+        ).render();
 
-            if let Ok(metadata) = path_metadata {
-                if metadata.is_dir() {
-                    let paths_result = read_dir(&untracked_path);
+        self.on_start(window);
+        true
+    }
 
-                    if let Ok(paths) = paths_result {
-                        for path in paths.flatten() {
-                            let line = Line::plain(&format!("?? {}", path.path().display()));
+    fn git_operation_actions(&mut self, window: &mut Window) -> bool {
+        let operation = match git::operation_in_progress() {
+            Some(operation) => operation,
+            None => return true
+        };
 
-                            if modified {
-                                added_modified.push(line);
-                            } else {
-                                added.push(line);
-                            }
-                        }
-                    }
-                } else if metadata.is_file() {
-                    let line = Line::plain(u);
+        let operation_window_height = operation.actions().len() as i32 + 1;
+        Renderer::new(
+            &mut OperationWindow::new(operation),
+            ScreenSize { lines: operation_window_height, cols: window.width() },
+            Position { x: 0, y: window.height() - operation_window_height },
+            screen()
+        ).render();
 
-                    if modified {
-                        added_modified.push(line);
-                    } else {
-                        added.push(line);
-                    }
-                }
-            }
-        }
+        self.on_start(window);
+        true
+    }
 
-        let mut deleted: Vec<Line> = git_status
-            .iter()
-            .filter(|c| c.starts_with(" D"))
-            .map(|c| Line::plain(c))
-            .collect();
+    fn refresh(&mut self, window: &mut Window) -> bool {
+        self.on_start(window);
+        true
+    }
+}
 
-        let mut unstaged: Vec<Line> = git_status
-            .iter()
-            .filter(|c| c.starts_with(" M") || c.starts_with("MM"))
-            .map(|c| Line::plain(c))
-            .collect();
+impl Component<MainWindow> for MainWindow {
+    fn on_start(&mut self, window: &mut Window) {
+        let upstream = if git::is_detached_head() { None } else { git::upstream() };
 
-        let mut staged: Vec<Line> = git_status
-            .iter()
-            .filter(|c| c.starts_with('M') || c.starts_with('A') || c.starts_with('D'))
-            .map(|c| Line::plain(c))
+        let mut rows: Vec<(Line, Option<Entry>)> = self.header(upstream.as_deref())
+            .into_iter()
+            .map(|l| (l, None))
             .collect();
 
-        let mut status: Vec<Line> = vec![
-            Line::new(vec![
-                Part::new("Head:", Some(vec![Style::Bold, Style::Underlined])),
-                Part::painted(
-                    &git::head_branch(),
-                    (0, 255, 255),
-                    (0, 0, 0)
-                ),
-                Part::plain(" "),
-                Part::plain(&git::last_commit())
-            ])
-        ];
-
-        let origin_hash = git::last_origin_commit_hash();
-        let local_hash = git::last_commit_hash();
-
-        if origin_hash != local_hash { // if HEAD different from origin HEAD
-            status.push(
-                 Line::new(vec![
-                     Part::new("Origin ", Some(vec![Style::Bold, Style::Underlined])),
-                     Part::painted(
-                         &git::origin_head_branch(),
-                         (255, 0, 0),
-                         (0, 0, 0)
-                     ),
-                     Part::plain(" "),
-                     Part::plain(&git::last_origin_commit())
-                 ])
-            );
-        }
-
-        status.push(Line::empty());
-
-
-        if !added.is_empty() {
-            status.push(
-                Line::new(vec![
-                    Part::new(
-                        &format!("Untracked files: ({})", added.len()),
-                        Some(vec![Style::Bold, Style::Underlined])
-                    )
-                ])
-            );
-            status.append(&mut added);
-
-            status.push(Line::empty());
-        }
-
-        if !added_modified.is_empty() {
-            status.push(
-                Line::new(vec![
-                    Part::new(
-                        &format!("Untracked (modified) files: ({})", added_modified.len()),
-                        Some(vec![Style::Bold, Style::Underlined])
-                    )]
-                )
-            );
-            status.append(&mut added_modified);
-
-            status.push(Line::empty());
+        if let Some(upstream) = &upstream {
+            rows.append(&mut self.commit_section(UNPUSHED_SECTION, git::unpushed_commits(upstream)));
+            rows.append(&mut self.commit_section(UNPULLED_SECTION, git::unpulled_commits(upstream)));
         }
 
-        if !deleted.is_empty() {
-            status.push(Line::from_str(
-                "Deleted files:",
-                Some(vec![Style::Bold, Style::Underlined]))
-            );
-            status.append(&mut deleted);
-            status.push(Line::empty());
-        }
+        rows.push((Line::empty(), None));
 
-        status.push(
-            Line::from_str(
-                &format!("Modified files: ({})", unstaged.len()),
-                Some(vec![Style::Bold, Style::Underlined])
-            )
-        );
-        if !unstaged.is_empty() {
-            status.append(&mut unstaged);
-
-            status.push(Line::empty());
-        }
+        self.sections = status_sections();
 
-        status.push(Line::empty());
+        for section in &self.sections {
+            // Modified files are always shown, to have a section to land in.
+            if section.files.is_empty() && section.title != MODIFIED_SECTION { continue }
 
-        if !staged.is_empty() {
-            status.push(
-                Line::new(vec![
-                    Part::new(
-                        &format!("Staged files: ({})", staged.len()),
-                        Some(vec![Style::Bold, Style::Underlined])
-                    )
-                ])
-            );
-            status.append(&mut staged);
+            rows.append(&mut self.file_section(section));
         }
 
-        if status.is_empty() {
-            status.push(Line::plain("No changes found."));
-        }
+        let (lines, entries) = rows.into_iter().unzip();
 
-        window.set_lines(status);
+        self.entries = entries;
+        window.set_lines(lines);
     }
 
     fn register_handlers(&self, handlers: &mut KeyHandlers<MainWindow>) {
         handlers.insert(KEY_LF, MainWindow::diff_file);
+        handlers.insert(KEY_HT, MainWindow::toggle_section);
         handlers.insert(KEY_B_LOWER, MainWindow::open_branch_window);
         handlers.insert(KEY_C_LOWER, MainWindow::git_checkout_file);
         handlers.insert(KEY_D_LOWER, MainWindow::delete_untracked_file);
         handlers.insert(KEY_L_LOWER, MainWindow::open_log_window);
+        handlers.insert(KEY_H_LOWER, MainWindow::open_file_history);
+        handlers.insert(KEY_B_UPPER, MainWindow::open_blame);
         handlers.insert(KEY_O_UPPER, MainWindow::open_in_file_manager);
         handlers.insert(KEY_T_LOWER, MainWindow::git_add_file);
         handlers.insert(KEY_U_LOWER, MainWindow::git_unstage_file);
+        handlers.insert(KEY_T_UPPER, MainWindow::open_tag_window);
+        handlers.insert(KEY_Z_LOWER, MainWindow::open_stash_window);
+        handlers.insert(KEY_Z_UPPER, MainWindow::git_stash_file);
         handlers.insert(KEY_COLON, MainWindow::open_command_window);
         handlers.insert(KEY_C_UPPER, MainWindow::git_commit_options);
         handlers.insert(KEY_P_UPPER, MainWindow::git_push_options);
+        handlers.insert(KEY_F_LOWER, MainWindow::git_fetch_options);
+        handlers.insert(KEY_F_UPPER, MainWindow::git_pull_options);
+        handlers.insert(KEY_R_LOWER, MainWindow::git_operation_actions);
         handlers.insert(KEY_R_UPPER, MainWindow::refresh);
+        handlers.insert(KEY_A_UPPER, MainWindow::git_add_all);
+        handlers.insert(KEY_V_UPPER, MainWindow::toggle_tree_view);
 
         register_search_handlers(handlers);
+        register_selection_handlers(handlers);
     }
 }
 
-impl SearchableComponent<MainWindow> for MainWindow {
-    fn term(&self) -> String {
-        self.term.clone()
+impl SelectableComponent<MainWindow> for MainWindow {
+    // The files around the index, or the ones under the section title.
+    fn section_range(&self, _window: &Window, index: usize) -> Option<(usize, usize)> {
+        let is_file = |e: &Option<Entry>| matches!(e, Some(Entry::File(_)) | Some(Entry::Directory(_, _)));
+
+        let (start, end) = match self.entries.get(index)? {
+            Some(Entry::File(_)) | Some(Entry::Directory(_, _)) => {
+                let start = self.entries[..index]
+                    .iter()
+                    .rposition(|e| !is_file(e))
+                    .map_or(0, |p| p + 1);
+
+                (start, index)
+            },
+            Some(Entry::Section(_)) => (index + 1, index),
+            None => return None
+        };
+
+        let end = self.entries[end..]
+            .iter()
+            .position(|e| !is_file(e))
+            .map_or(self.entries.len(), |p| end + p);
+
+        if start >= end { return None }
+
+        Some((start, end - 1))
     }
+}
+
+impl SearchableComponent<MainWindow> for MainWindow {}
+
+// Path of the file in a status line, the new one for renames.
+fn tree_path(status_line: &str) -> &str {
+    let path = status_line[3..].trim();
+
+    match path.find(" -> ") {
+        Some(arrow) => &path[arrow + 4..],
+        None => path
+    }
+}
+
+// Lists the files a destructive action applies to, only the first
+// few of them if there are too many to fit on the prompt line.
+fn describe_files(paths: &[&str]) -> String {
+    if paths.len() == 1 {
+        return format!("file '{}'", paths[0])
+    }
+
+    let mut listed = paths
+        .iter()
+        .take(MAX_DESCRIBED_FILES)
+        .copied()
+        .collect::<Vec<&str>>()
+        .join(", ");
 
-    fn set_term(&mut self, term: String) {
-        self.term = term;
+    if paths.len() > MAX_DESCRIBED_FILES {
+        listed.push_str(&format!(" and {} more", paths.len() - MAX_DESCRIBED_FILES));
     }
+
+    format!("{} files ({})", paths.len(), listed)
+}
+
+// Status lines of the changed files grouped into the sections
+// they are shown in, a file can be both modified and staged.
+fn status_sections() -> Vec<StatusSection> {
+    let git_status: Vec<String> = git::status();
+
+    let (untracked_modified, untracked): (Vec<String>, Vec<String>) = git_status
+        .iter()
+        .filter(|c| c.starts_with("??") || c.starts_with("AM"))
+        .cloned()
+        .partition(|c| c.starts_with("AM"));
+
+    let select = |filter: fn(&str) -> bool| -> Vec<String> {
+        git_status
+            .iter()
+            .filter(|c| filter(c))
+            .cloned()
+            .collect()
+    };
+
+    vec![
+        StatusSection {
+            title: CONFLICTS_SECTION,
+            files: select(|c| c.len() > 3 && git::is_conflicted(c))
+        },
+        StatusSection { title: UNTRACKED_SECTION, files: untracked },
+        StatusSection { title: UNTRACKED_MODIFIED_SECTION, files: untracked_modified },
+        StatusSection {
+            title: DELETED_SECTION,
+            files: select(|c| c.starts_with(" D"))
+        },
+        StatusSection {
+            title: MODIFIED_SECTION,
+            files: select(|c| c.starts_with(" M") || c.starts_with("MM"))
+        },
+        StatusSection {
+            title: STAGED_SECTION,
+            files: select(|c| {
+                (c.starts_with('M') || c.starts_with('A') || c.starts_with('D')) && !git::is_conflicted(c)
+            })
+        }
+    ]
 }
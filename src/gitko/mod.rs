@@ -1,13 +1,30 @@
+pub mod tag_window;
 pub mod log_window;
 pub mod diff_window;
+pub mod blame_window;
+pub mod stash_window;
 pub mod main_window;
 pub mod text_window;
 pub mod diff_display;
 pub mod input_window;
 pub mod output_window;
+pub mod remote_window;
+pub mod remotes_window;
 pub mod branch_window;
 pub mod prompt_window;
+pub mod search_prompt_window;
+pub mod rebase_window;
 pub mod command_window;
+pub mod conflict_window;
+pub mod operation_window;
+pub mod file_history_window;
+pub mod log_filter_window;
+pub mod file_revision_window;
+pub mod history_search_window;
+pub mod branch_cleanup_window;
 pub mod commit_diff_window;
+pub mod pull_options_window;
 pub mod push_options_window;
+pub mod fetch_options_window;
+pub mod commit_detail_window;
 pub mod commit_options_window;
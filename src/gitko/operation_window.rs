@@ -0,0 +1,51 @@
+use crate::git;
+use crate::git::Operation;
+use crate::gitko::output_window::show_output;
+use gitko_render::{Component, KeyHandlers, Line, Window, Style};
+
+use gitko_common::ascii_table::{KEY_LF};
+
+pub struct OperationWindow {
+    operation: Operation
+}
+
+impl OperationWindow {
+    pub fn new(operation: Operation) -> OperationWindow {
+        OperationWindow { operation }
+    }
+
+    fn git_continue_operation(&mut self, window: &mut Window) -> bool {
+        let action = window.get_cursor_line().trim().to_owned();
+        if !action.starts_with("--") { return true }
+
+        window.clear();
+
+        show_output(git::continue_operation(&self.operation, &action));
+
+        false
+    }
+}
+
+impl Component<OperationWindow> for OperationWindow {
+    fn on_start(&mut self, window: &mut Window) {
+        let mut lines = vec![
+            Line::from_str(
+                &format!("{} in progress:", self.operation.name()),
+                Some(vec![Style::Bold, Style::Underlined])
+            )
+        ];
+
+        lines.extend(
+            self.operation
+                .actions()
+                .iter()
+                .map(|a| Line::plain(a))
+        );
+
+        window.set_lines(lines);
+    }
+
+    fn register_handlers(&self, handlers: &mut KeyHandlers<OperationWindow>) {
+        handlers.insert(KEY_LF, OperationWindow::git_continue_operation);
+    }
+}
@@ -1,4 +1,6 @@
-use gitko_render::{Component, KeyHandlers, Line, Window, Style};
+use crate::screen;
+use crate::{max_height, max_width};
+use gitko_render::{Component, KeyHandlers, Line, Renderer, ScreenSize, Position, Window, Style};
 
 use gitko_common::ascii_table::{KEY_ETB, KEY_LF};
 
@@ -42,3 +44,17 @@ impl Component<OutputWindow> for OutputWindow {
         handlers.insert(KEY_ETB, OutputWindow::close);
     }
 }
+
+// Renders the command output at the bottom of the screen.
+// Does nothing if there is no output to show.
+pub fn show_output(output: Vec<String>) {
+    if output.is_empty() { return }
+
+    let output_window_height = output.len() as i32 + 1;
+    Renderer::new(
+        &mut OutputWindow { output },
+        ScreenSize { lines: output_window_height, cols: max_width() },
+        Position { x: 0, y: max_height() - output_window_height },
+        screen()
+    ).render();
+}
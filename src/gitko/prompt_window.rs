@@ -1,4 +1,8 @@
-use gitko_render::{Component, KeyHandlers, Line, Window};
+use std::cell::Cell;
+
+use crate::screen;
+use crate::max_height;
+use gitko_render::{Component, KeyHandlers, Line, Renderer, ScreenSize, Position, Window};
 
 use gitko_common::ascii_table::{KEY_N_LOWER, KEY_Y_LOWER};
 
@@ -39,3 +43,18 @@ impl<TYes: Fn(), TNo: Fn()> Component<PromptWindow<TYes, TNo>> for PromptWindow<
         handlers.insert(KEY_N_LOWER, PromptWindow::no);
     }
 }
+
+// Asks the yes/no question at the bottom of the screen
+// and returns whether it was answered with yes.
+pub fn confirm(message: &str) -> bool {
+    let confirmed = Cell::new(false);
+
+    Renderer::new(
+        &mut PromptWindow::new(message, || confirmed.set(true), || { /* Do nothing on no. */ }),
+        ScreenSize { lines: 1, cols: 0 },
+        Position { x: 0, y: max_height() - 1 },
+        screen()
+    ).render();
+
+    confirmed.get()
+}
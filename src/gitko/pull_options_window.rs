@@ -0,0 +1,55 @@
+use crate::git;
+use crate::gitko::input_window::read_input;
+use crate::gitko::output_window::show_output;
+use crate::gitko::remote_window::select_remote;
+use gitko_render::{Line, KeyHandlers, Component, Window};
+
+use gitko_common::ascii_table::{KEY_LF};
+
+pub struct PullOptionsWindow { }
+
+impl PullOptionsWindow {
+    fn git_pull(&mut self, window: &mut Window) -> bool {
+        let line = window.get_cursor_line().trim().to_owned();
+
+        let args = if line.is_empty() {
+            None
+        } else {
+            Some(line.split_whitespace().collect())
+        };
+
+        window.clear();
+
+        let branch = read_input("Enter branch to pull (leave empty for the upstream of the current branch):");
+
+        // Without a branch git pulls the configured upstream, whatever its remote and name.
+        if branch.is_empty() {
+            show_output(git::pull(args, None, None));
+            return false
+        }
+
+        let remote = match select_remote() {
+            Some(remote) => remote,
+            None => return false
+        };
+
+        show_output(git::pull(args, Some(&remote), Some(&branch)));
+
+        false
+    }
+}
+
+impl Component<PullOptionsWindow> for PullOptionsWindow {
+    fn on_start(&mut self, window: &mut Window) {
+        window.set_lines(
+            ["", "--rebase", "--ff-only", "--prune", "--rebase --prune"]
+                .iter()
+                .map(|s| Line::plain(s))
+                .collect()
+        );
+    }
+
+    fn register_handlers(&self, handlers: &mut KeyHandlers<PullOptionsWindow>) {
+        handlers.insert(KEY_LF, PullOptionsWindow::git_pull);
+    }
+}
@@ -1,8 +1,8 @@
-use crate::screen;
 use crate::git;
-use crate::max_height;
-use crate::gitko::output_window::OutputWindow;
-use gitko_render::{Renderer, Line, KeyHandlers, Component, Window, ScreenSize, Position};
+use crate::gitko::input_window::read_input;
+use crate::gitko::output_window::show_output;
+use crate::gitko::remote_window::select_remote;
+use gitko_render::{Line, KeyHandlers, Component, Window};
 
 use gitko_common::ascii_table::{KEY_LF};
 
@@ -15,21 +15,20 @@ impl PushOptionsWindow {
         let args = if line.is_empty() {
             None
         } else {
-            Some(vec![line.as_str()])
+            Some(line.split_whitespace().collect())
         };
 
         window.clear();
 
-        let output = git::push(args);
-        if output.is_empty() { return false }
+        let remote = match select_remote() {
+            Some(remote) => remote,
+            None => return false
+        };
+
+        let refspec = read_input("Enter refspec (leave empty for the current branch):");
+        let refspec = if refspec.is_empty() { None } else { Some(refspec.as_str()) };
 
-        let output_window_height = output.len() as i32 + 1;
-        Renderer::new(
-            &mut OutputWindow { output },
-            ScreenSize { lines: output_window_height, cols: window.width() },
-            Position { x: 0, y: max_height() - output_window_height },
-            screen()
-        ).render();
+        show_output(git::push(args, &remote, refspec));
 
         false
     }
@@ -38,7 +37,7 @@ impl PushOptionsWindow {
 impl Component<PushOptionsWindow> for PushOptionsWindow {
     fn on_start(&mut self, window: &mut Window) {
         window.set_lines(
-            vec!["", "--force-with-lease"]
+            ["", "--force-with-lease", "--set-upstream", "--set-upstream --force-with-lease"]
                 .iter()
                 .map(|s| Line::plain(s))
                 .collect()
@@ -0,0 +1,213 @@
+use std::fs::{remove_file, write};
+
+use crate::git;
+use crate::gitko::output_window::show_output;
+use gitko_render::{Component, KeyHandlers, Line, Window, Part, Style, RGB};
+
+use gitko_common::ascii_table::{KEY_D_LOWER, KEY_E_LOWER, KEY_F_LOWER, KEY_J_UPPER, KEY_K_UPPER, KEY_LF,
+                                KEY_P_LOWER, KEY_R_LOWER, KEY_S_LOWER};
+
+// Title and an empty line are rendered above the todo list.
+const HEADER_LINES: usize = 2;
+
+#[derive(Clone, Copy)]
+enum RebaseAction {
+    Pick,
+    Reword,
+    Edit,
+    Squash,
+    Fixup,
+    Drop
+}
+
+impl RebaseAction {
+    fn name(&self) -> &str {
+        match self {
+            RebaseAction::Pick   => "pick",
+            RebaseAction::Reword => "reword",
+            RebaseAction::Edit   => "edit",
+            RebaseAction::Squash => "squash",
+            RebaseAction::Fixup  => "fixup",
+            RebaseAction::Drop   => "drop"
+        }
+    }
+
+    fn color(&self) -> RGB {
+        match self {
+            RebaseAction::Pick   => (0, 255, 0),
+            RebaseAction::Reword => (0, 255, 255),
+            RebaseAction::Edit   => (255, 255, 0),
+            RebaseAction::Squash | RebaseAction::Fixup => (255, 0, 255),
+            RebaseAction::Drop   => (255, 0, 0)
+        }
+    }
+}
+
+struct RebaseTodo {
+    action: RebaseAction,
+    // Abbreviated hash followed by the subject.
+    commit: String
+}
+
+pub struct RebaseWindow {
+    commit_hash: String,
+    todo: Vec<RebaseTodo>
+}
+
+impl RebaseWindow {
+    pub fn new(commit_hash: &str) -> RebaseWindow {
+        let todo = git::rebase_commits(commit_hash)
+            .into_iter()
+            .map(|commit| RebaseTodo { action: RebaseAction::Pick, commit })
+            .collect();
+
+        RebaseWindow {
+            commit_hash: commit_hash.to_owned(),
+            todo
+        }
+    }
+
+    fn todo_index(&self, window: &Window) -> Option<usize> {
+        let index = window.cursor_index().checked_sub(HEADER_LINES)?;
+
+        if index < self.todo.len() { Some(index) } else { None }
+    }
+
+    fn set_action(&mut self, window: &mut Window, action: RebaseAction) -> bool {
+        if let Some(index) = self.todo_index(window) {
+            self.todo[index].action = action;
+            self.on_start(window);
+        }
+
+        true
+    }
+
+    fn pick(&mut self, window: &mut Window) -> bool {
+        self.set_action(window, RebaseAction::Pick)
+    }
+
+    fn reword(&mut self, window: &mut Window) -> bool {
+        self.set_action(window, RebaseAction::Reword)
+    }
+
+    fn edit(&mut self, window: &mut Window) -> bool {
+        self.set_action(window, RebaseAction::Edit)
+    }
+
+    fn squash(&mut self, window: &mut Window) -> bool {
+        self.set_action(window, RebaseAction::Squash)
+    }
+
+    fn fixup(&mut self, window: &mut Window) -> bool {
+        self.set_action(window, RebaseAction::Fixup)
+    }
+
+    fn drop_commit(&mut self, window: &mut Window) -> bool {
+        self.set_action(window, RebaseAction::Drop)
+    }
+
+    fn move_commit_down(&mut self, window: &mut Window) -> bool {
+        if let Some(index) = self.todo_index(window) {
+            if index + 1 < self.todo.len() {
+                self.todo.swap(index, index + 1);
+                self.on_start(window);
+                window.move_cursor_to(HEADER_LINES + index + 1);
+            }
+        }
+
+        true
+    }
+
+    fn move_commit_up(&mut self, window: &mut Window) -> bool {
+        if let Some(index) = self.todo_index(window) {
+            if index > 0 {
+                self.todo.swap(index, index - 1);
+                self.on_start(window);
+                window.move_cursor_to(HEADER_LINES + index - 1);
+            }
+        }
+
+        true
+    }
+
+    fn start_rebase(&mut self, window: &mut Window) -> bool {
+        if self.todo.is_empty() { return false }
+
+        if git::has_uncommitted_changes() {
+            show_output(vec!["Commit or stash the changes before rebasing.".to_owned()]);
+            return true
+        }
+
+        let todo: String = self.todo
+            .iter()
+            .map(|t| format!("{} {}\n", t.action.name(), t.commit))
+            .collect();
+
+        // Kept in the git directory, so that gitko instances in other repositories don't share it.
+        let todo_path = git::git_path("gitko-rebase-todo");
+        write(&todo_path, todo).expect("Could not write the rebase todo list");
+
+        let base = if git::has_parent(&self.commit_hash) {
+            Some(format!("{}^", self.commit_hash))
+        } else {
+            None
+        };
+
+        window.clear();
+
+        let mut output = git::rebase_interactive(base.as_deref(), &todo_path.to_string_lossy());
+        let _ = remove_file(&todo_path);
+
+        // The rebase either stopped at a commit or is no longer in progress.
+        match git::operation_in_progress() {
+            Some(operation) => output.push(format!("{} stopped: {}", operation.name(), operation.description())),
+            None => output.extend(git::branch_status())
+        }
+
+        show_output(output);
+
+        false
+    }
+}
+
+impl Component<RebaseWindow> for RebaseWindow {
+    fn on_start(&mut self, window: &mut Window) {
+        let mut lines = vec![
+            Line::from_str(
+                &format!("Rebase from {} (p, r, e, s, f, d to set action, J/K to move, Enter to start):", self.commit_hash),
+                Some(vec![Style::Bold, Style::Underlined])
+            ),
+            Line::empty()
+        ];
+
+        lines.extend(
+            self.todo
+                .iter()
+                .map(|t| {
+                    Line::new(vec![
+                        Part::painted(
+                            &format!("{:<7}", t.action.name()),
+                            t.action.color(),
+                            (0, 0, 0)
+                        ),
+                        Part::plain(" "),
+                        Part::plain(&t.commit)
+                    ])
+                })
+        );
+
+        window.set_lines(lines);
+    }
+
+    fn register_handlers(&self, handlers: &mut KeyHandlers<RebaseWindow>) {
+        handlers.insert(KEY_P_LOWER, RebaseWindow::pick);
+        handlers.insert(KEY_R_LOWER, RebaseWindow::reword);
+        handlers.insert(KEY_E_LOWER, RebaseWindow::edit);
+        handlers.insert(KEY_S_LOWER, RebaseWindow::squash);
+        handlers.insert(KEY_F_LOWER, RebaseWindow::fixup);
+        handlers.insert(KEY_D_LOWER, RebaseWindow::drop_commit);
+        handlers.insert(KEY_J_UPPER, RebaseWindow::move_commit_down);
+        handlers.insert(KEY_K_UPPER, RebaseWindow::move_commit_up);
+        handlers.insert(KEY_LF, RebaseWindow::start_rebase);
+    }
+}
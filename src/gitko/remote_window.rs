@@ -0,0 +1,71 @@
+use crate::git;
+use crate::screen;
+use crate::{max_height, max_width};
+use crate::gitko::output_window::show_output;
+use gitko_render::{Component, KeyHandlers, Line, Renderer, ScreenSize, Position, Window, Style};
+
+use gitko_common::ascii_table::{KEY_LF};
+
+pub struct RemoteWindow {
+    remotes: Vec<String>,
+    pub remote: Option<String>
+}
+
+impl RemoteWindow {
+    pub fn new(remotes: Vec<String>) -> RemoteWindow {
+        RemoteWindow { remotes, remote: None }
+    }
+
+    fn select_remote(&mut self, window: &mut Window) -> bool {
+        let line = window.get_cursor_line().trim().to_owned();
+        if !self.remotes.contains(&line) { return true }
+
+        self.remote = Some(line);
+
+        false
+    }
+}
+
+impl Component<RemoteWindow> for RemoteWindow {
+    fn on_start(&mut self, window: &mut Window) {
+        let mut lines = vec![
+            Line::from_str("Remotes:", Some(vec![Style::Bold, Style::Underlined]))
+        ];
+
+        lines.extend(self.remotes.iter().map(|r| Line::plain(r)));
+
+        window.set_lines(lines);
+    }
+
+    fn register_handlers(&self, handlers: &mut KeyHandlers<RemoteWindow>) {
+        handlers.insert(KEY_LF, RemoteWindow::select_remote);
+    }
+}
+
+// Picks the remote to run a command against. Skips the
+// selection altogether if there is only one remote configured.
+// Returns None if there are no remotes, telling so, or the selection was cancelled.
+pub fn select_remote() -> Option<String> {
+    let remotes = git::remotes();
+
+    if remotes.is_empty() {
+        show_output(vec!["No remotes configured.".to_owned()]);
+        return None
+    }
+
+    if remotes.len() == 1 {
+        return remotes.first().cloned()
+    }
+
+    let remote_window_height = remotes.len() as i32 + 1;
+    let mut remote_window = RemoteWindow::new(remotes);
+
+    Renderer::new(
+        &mut remote_window,
+        ScreenSize { lines: remote_window_height, cols: max_width() },
+        Position { x: 0, y: max_height() - remote_window_height },
+        screen()
+    ).render();
+
+    remote_window.remote
+}
@@ -0,0 +1,129 @@
+use crate::git;
+use crate::screen;
+use crate::gitko::input_window::read_input;
+use crate::gitko::output_window::show_output;
+use crate::gitko::prompt_window::PromptWindow;
+use gitko_render::{Component, KeyHandlers, Line, Renderer, ScreenSize, Window, Position, Part, Style};
+
+use gitko_common::ascii_table::{KEY_A_LOWER, KEY_D_LOWER, KEY_P_LOWER, KEY_R_LOWER};
+
+// Title and an empty line are rendered above the remotes.
+const HEADER_LINES: usize = 2;
+
+pub struct RemotesWindow {
+    // Remote name and fetch url pairs.
+    remotes: Vec<(String, String)>
+}
+
+impl RemotesWindow {
+    pub fn new() -> RemotesWindow {
+        RemotesWindow { remotes: vec![] }
+    }
+
+    fn selected_remote(&self, window: &Window) -> Option<String> {
+        let index = window.cursor_index().checked_sub(HEADER_LINES)?;
+
+        self.remotes
+            .get(index)
+            .map(|(name, _)| name.clone())
+    }
+
+    fn add_remote(&mut self, window: &mut Window) -> bool {
+        let name = read_input("Enter name of the new remote:");
+
+        if !name.is_empty() {
+            let url = read_input(&format!("Enter url of the remote '{}':", name));
+            show_output(git::add_remote(&name, &url));
+        }
+
+        self.on_start(window);
+
+        true
+    }
+
+    fn rename_remote(&mut self, window: &mut Window) -> bool {
+        if let Some(remote) = self.selected_remote(window) {
+            let new_name = read_input(&format!("Enter new name of the remote '{}':", remote));
+
+            if !new_name.is_empty() {
+                show_output(git::rename_remote(&remote, &new_name));
+            }
+        }
+
+        self.on_start(window);
+
+        true
+    }
+
+    fn open_remove_remote_prompt(&mut self, window: &mut Window) -> bool {
+        if let Some(remote) = self.selected_remote(window) {
+            Renderer::new(
+                &mut PromptWindow::new(
+                    &format!("Are you sure you want to remove remote '{}'? y/n", remote),
+                    || { show_output(git::remove_remote(&remote)); },
+                    || { /* Do nothing on no. */ }
+                ),
+                ScreenSize { lines: 1, cols: 0 },
+                Position { x: 0, y: window.height() - 1 },
+                screen()
+            ).render();
+        }
+
+        self.on_start(window);
+
+        true
+    }
+
+    fn prune_remote(&mut self, window: &mut Window) -> bool {
+        if let Some(remote) = self.selected_remote(window) {
+            show_output(git::prune_remote(&remote));
+        }
+
+        self.on_start(window);
+
+        true
+    }
+}
+
+impl Component<RemotesWindow> for RemotesWindow {
+    fn on_start(&mut self, window: &mut Window) {
+        self.remotes = git::remotes_verbose()
+            .iter()
+            .filter_map(|r| {
+                let mut fields = r.split_whitespace();
+                Some((fields.next()?.to_owned(), fields.next().unwrap_or_default().to_owned()))
+            })
+            .collect();
+
+        let mut lines = vec![
+            Line::from_str(
+                &format!("Remotes: ({}) a: add, r: rename, d: remove, p: prune", self.remotes.len()),
+                Some(vec![Style::Bold, Style::Underlined])
+            ),
+            Line::empty()
+        ];
+
+        let name_width = self.remotes.iter().map(|(n, _)| n.len()).max().unwrap_or(0);
+
+        lines.extend(
+            self.remotes
+                .iter()
+                .map(|(name, url)| {
+                    Line::new(vec![
+                        Part::painted(&format!("{:<width$}", name, width = name_width), (255, 0, 0), (0, 0, 0)),
+                        Part::plain(" "),
+                        Part::plain(url)
+                    ])
+                })
+        );
+
+        window.set_lines(lines);
+    }
+
+    fn register_handlers(&self, handlers: &mut KeyHandlers<RemotesWindow>) {
+        handlers.insert(KEY_A_LOWER, RemotesWindow::add_remote);
+        handlers.insert(KEY_R_LOWER, RemotesWindow::rename_remote);
+        handlers.insert(KEY_D_LOWER, RemotesWindow::open_remove_remote_prompt);
+        handlers.insert(KEY_P_LOWER, RemotesWindow::prune_remote);
+    }
+}
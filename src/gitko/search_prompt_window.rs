@@ -0,0 +1,124 @@
+use gitko_render::{Component, Line, Window, Part, Style, SearchFlags, SearchPattern};
+
+use gitko_common::ascii_table::{KEY_BS, KEY_DC2, KEY_DEL, KEY_ENQ, KEY_ETB, KEY_LF, KEY_STX};
+
+// Called with the search whenever the term or the flags change,
+// None while the term is empty or not a valid regex.
+type ChangeListener<'a> = Box<dyn FnMut(Option<&SearchPattern>) + 'a>;
+
+// Reads a search term, the flags being toggled with control keys
+// while typing. Invalid regexes are shown above the term.
+pub struct SearchPromptWindow<'a> {
+    title: &'static str,
+    text: String,
+    // None if the term is not matched with the flags, which are then not shown.
+    flags: Option<SearchFlags>,
+    error: Option<String>,
+    on_change: ChangeListener<'a>,
+    pattern: Option<SearchPattern>,
+    pub cancelled: bool
+}
+
+impl<'a> SearchPromptWindow<'a> {
+    pub fn new(
+        title: &'static str,
+        flags: Option<SearchFlags>,
+        on_change: impl FnMut(Option<&SearchPattern>) + 'a) -> SearchPromptWindow<'a> {
+        SearchPromptWindow {
+            title,
+            text: "".to_owned(),
+            flags,
+            error: None,
+            on_change: Box::new(on_change),
+            pattern: None,
+            cancelled: false
+        }
+    }
+
+    fn compile(&mut self) {
+        self.pattern = None;
+        self.error = None;
+
+        if self.text.is_empty() { return }
+
+        match SearchPattern::new(&self.text, self.flags.unwrap_or_default()) {
+            Ok(pattern) => self.pattern = Some(pattern),
+            Err(error) => self.error = Some(error)
+        }
+    }
+
+    fn toggle_flag(&mut self, key: i32) {
+        let Some(flags) = self.flags.as_mut() else { return };
+
+        match key {
+            KEY_DC2 => flags.regex = !flags.regex,
+            KEY_ENQ => flags.smart_case = !flags.smart_case,
+            _ => flags.whole_word = !flags.whole_word
+        }
+    }
+
+    fn lines(&self) -> Vec<Line> {
+        let flag = |key: &str, name: &str, on: bool| {
+            let style = if on { Some(vec![Style::Bold]) } else { None };
+            Part::new(&format!(" {}: {} [{}]", key, name, if on { "x" } else { " " }), style)
+        };
+
+        let mut status = vec![Part::new(self.title, Some(vec![Style::Bold]))];
+
+        if let Some(flags) = self.flags {
+            status.push(flag("C-r", "regex", flags.regex));
+            status.push(flag("C-e", "smart case", flags.smart_case));
+            status.push(flag("C-b", "whole word", flags.whole_word));
+        }
+
+        if let Some(error) = &self.error {
+            status.push(Part::painted(&format!(" {}", error), (255, 0, 0), (0, 0, 0)));
+        }
+
+        vec![
+            Line::new(status),
+            Line::new(vec![
+                Part::plain(&format!("/{}", self.text)),
+                Part::painted(" ", (0, 0, 0), (255, 255, 255))
+            ])
+        ]
+    }
+}
+
+impl<'a> Component<SearchPromptWindow<'a>> for SearchPromptWindow<'a> {
+    fn on_start(&mut self, window: &mut Window) {
+        window.show_cursor(false);
+        window.set_lines(self.lines());
+    }
+
+    // Handles the keys itself until the search is either confirmed or cancelled.
+    fn on_render(&mut self, window: &mut Window) -> bool {
+        loop {
+            match window.listen_input() {
+                KEY_LF if self.error.is_none() => break,
+                KEY_ETB => {
+                    self.cancelled = true;
+                    break
+                },
+                key @ (KEY_DC2 | KEY_ENQ | KEY_STX) => self.toggle_flag(key),
+                KEY_DEL | KEY_BS => { self.text.pop(); },
+                key => match char::from_u32(key as u32) {
+                    Some(c) if !c.is_control() => self.text.push(c),
+                    _ => continue
+                }
+            }
+
+            self.compile();
+            (self.on_change)(self.pattern.as_ref());
+
+            window.set_lines(self.lines());
+            window.redraw();
+        }
+
+        false
+    }
+
+    fn on_exit(&mut self, window: &mut Window) {
+        window.show_cursor(true);
+    }
+}
@@ -0,0 +1,155 @@
+use crate::git;
+use crate::screen;
+use crate::gitko::input_window::read_input;
+use crate::gitko::output_window::show_output;
+use crate::gitko::prompt_window::PromptWindow;
+use crate::gitko::commit_diff_window::CommitDiffWindow;
+use gitko_render::{Component, KeyHandlers, Line, Renderer, ScreenSize, Window, Position};
+
+use gitko_common::ascii_table::{KEY_A_LOWER, KEY_D_LOWER, KEY_LF, KEY_N_LOWER, KEY_P_LOWER};
+
+pub struct StashWindow { }
+
+impl StashWindow {
+    fn show_stash(&mut self, window: &mut Window) -> bool {
+        if let Some(stash) = parse_stash_ref(&window.get_cursor_line()) {
+            Renderer::new(
+                &mut CommitDiffWindow::stash(stash),
+                ScreenSize::max(),
+                Position::default(),
+                screen()
+            ).render();
+        }
+
+        true
+    }
+
+    fn create_stash(&mut self, window: &mut Window) -> bool {
+        Renderer::new(
+            &mut StashOptionsWindow { },
+            ScreenSize { lines: 3, cols: window.width() },
+            Position { x: 0, y: window.height() - 3 },
+            screen()
+        ).render();
+
+        self.on_start(window);
+
+        true
+    }
+
+    fn apply_stash(&mut self, window: &mut Window) -> bool {
+        if let Some(stash) = parse_stash_ref(&window.get_cursor_line()) {
+            show_output(git::stash_apply(stash));
+        }
+
+        self.on_start(window);
+
+        true
+    }
+
+    fn pop_stash(&mut self, window: &mut Window) -> bool {
+        if let Some(stash) = parse_stash_ref(&window.get_cursor_line()) {
+            show_output(git::stash_pop(stash));
+        }
+
+        self.on_start(window);
+
+        true
+    }
+
+    fn open_drop_stash_prompt(&mut self, window: &mut Window) -> bool {
+        let line = window.get_cursor_line();
+
+        if let Some(stash) = parse_stash_ref(&line) {
+            Renderer::new(
+                &mut PromptWindow::new(
+                    &format!("Are you sure you want to drop '{}'? y/n", stash),
+                    || { git::stash_drop(stash); },
+                    || { /* Do nothing on no. */ }
+                ),
+                ScreenSize { lines: 1, cols: 0 },
+                Position { x: 0, y: window.height() - 1 },
+                screen()
+            ).render();
+        }
+
+        self.on_start(window);
+
+        true
+    }
+}
+
+impl Component<StashWindow> for StashWindow {
+    fn on_start(&mut self, window: &mut Window) {
+        let stashes = git::stash_list();
+
+        if stashes.is_empty() {
+            window.set_lines(vec![Line::plain("No stashes found.")]);
+            return
+        }
+
+        window.set_lines(
+            stashes
+                .iter()
+                .map(|s| Line::plain(s))
+                .collect()
+        );
+    }
+
+    fn register_handlers(&self, handlers: &mut KeyHandlers<StashWindow>) {
+        handlers.insert(KEY_LF, StashWindow::show_stash);
+        handlers.insert(KEY_A_LOWER, StashWindow::apply_stash);
+        handlers.insert(KEY_D_LOWER, StashWindow::open_drop_stash_prompt);
+        handlers.insert(KEY_N_LOWER, StashWindow::create_stash);
+        handlers.insert(KEY_P_LOWER, StashWindow::pop_stash);
+    }
+}
+
+struct StashOptionsWindow { }
+
+impl StashOptionsWindow {
+    fn git_stash(&mut self, window: &mut Window) -> bool {
+        let line = window.get_cursor_line().trim().to_owned();
+
+        let args = if line.is_empty() {
+            None
+        } else {
+            Some(vec![line.as_str()])
+        };
+
+        window.clear();
+
+        let message = read_input("Enter stash message (leave empty for the default):");
+        let message = if message.is_empty() { None } else { Some(message.as_str()) };
+
+        show_output(git::stash_push(args, message, None));
+
+        false
+    }
+}
+
+impl Component<StashOptionsWindow> for StashOptionsWindow {
+    fn on_start(&mut self, window: &mut Window) {
+        window.set_lines(
+            ["", "--include-untracked", "--keep-index"]
+                .iter()
+                .map(|s| Line::plain(s))
+                .collect()
+        );
+    }
+
+    fn register_handlers(&self, handlers: &mut KeyHandlers<StashOptionsWindow>) {
+        handlers.insert(KEY_LF, StashOptionsWindow::git_stash);
+    }
+}
+
+// Stash list lines look like "stash@{0}: WIP on master: ...".
+fn parse_stash_ref(line: &str) -> Option<&str> {
+    let stash = line.split(':').next()?.trim();
+
+    if stash.starts_with("stash@{") {
+        Some(stash)
+    } else {
+        None
+    }
+}
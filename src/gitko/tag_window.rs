@@ -0,0 +1,180 @@
+use crate::git;
+use crate::git::Tag;
+use crate::screen;
+use crate::gitko::output_window::show_output;
+use crate::gitko::prompt_window::PromptWindow;
+use crate::gitko::remote_window::select_remote;
+use crate::gitko::commit_detail_window::CommitDetailWindow;
+use crate::searchable::{SearchableComponent, register_search_handlers};
+use gitko_render::{Component, KeyHandlers, Line, Renderer, ScreenSize, Window, Position, Part, Style};
+
+use gitko_common::ascii_table::{KEY_D_LOWER, KEY_D_UPPER, KEY_LF, KEY_P_LOWER, KEY_P_UPPER, KEY_S_LOWER};
+
+// Title and an empty line are rendered above the tags.
+const HEADER_LINES: usize = 2;
+
+const SORT_BY_VERSION: &str = "-version:refname";
+const SORT_BY_DATE: &str = "-creatordate";
+
+pub struct TagWindow {
+    sort: &'static str,
+    tags: Vec<Tag>
+}
+
+impl TagWindow {
+    pub fn new() -> TagWindow {
+        TagWindow {
+            sort: SORT_BY_VERSION,
+            tags: vec![]
+        }
+    }
+
+    fn selected_tag(&self, window: &Window) -> Option<&Tag> {
+        let index = window.cursor_index().checked_sub(HEADER_LINES)?;
+        self.tags.get(index)
+    }
+
+    fn show_tag(&mut self, window: &mut Window) -> bool {
+        if let Some(tag) = self.selected_tag(window) {
+            Renderer::new(
+                &mut CommitDetailWindow::new(&tag.target),
+                ScreenSize::max(),
+                Position::default(),
+                screen()
+            ).render();
+        }
+
+        true
+    }
+
+    fn toggle_sort(&mut self, window: &mut Window) -> bool {
+        self.sort = if self.sort == SORT_BY_VERSION { SORT_BY_DATE } else { SORT_BY_VERSION };
+        self.on_start(window);
+
+        true
+    }
+
+    fn open_delete_tag_prompt(&mut self, window: &mut Window) -> bool {
+        if let Some(tag) = self.selected_tag(window) {
+            let name = &tag.name;
+
+            Renderer::new(
+                &mut PromptWindow::new(
+                    &format!("Are you sure you want to delete tag '{}'? y/n", name),
+                    || { show_output(git::delete_tag(name)); },
+                    || { /* Do nothing on no. */ }
+                ),
+                ScreenSize { lines: 1, cols: 0 },
+                Position { x: 0, y: window.height() - 1 },
+                screen()
+            ).render();
+        }
+
+        self.on_start(window);
+
+        true
+    }
+
+    fn open_delete_remote_tag_prompt(&mut self, window: &mut Window) -> bool {
+        if let Some(tag) = self.selected_tag(window) {
+            let name = &tag.name;
+
+            if let Some(remote) = select_remote() {
+                Renderer::new(
+                    &mut PromptWindow::new(
+                        &format!("Are you sure you want to delete tag '{}' on '{}'? y/n", name, remote),
+                        || { show_output(git::delete_remote_tag(&remote, name)); },
+                        || { /* Do nothing on no. */ }
+                    ),
+                    ScreenSize { lines: 1, cols: 0 },
+                    Position { x: 0, y: window.height() - 1 },
+                    screen()
+                ).render();
+            }
+        }
+
+        self.on_start(window);
+
+        true
+    }
+
+    fn push_tag(&mut self, window: &mut Window) -> bool {
+        if let Some(tag) = self.selected_tag(window) {
+            if let Some(remote) = select_remote() {
+                show_output(git::push_tag(&remote, &tag.name));
+            }
+        }
+
+        self.on_start(window);
+
+        true
+    }
+
+    fn push_tags(&mut self, window: &mut Window) -> bool {
+        if let Some(remote) = select_remote() {
+            show_output(git::push_tags(&remote));
+        }
+
+        self.on_start(window);
+
+        true
+    }
+}
+
+impl Component<TagWindow> for TagWindow {
+    fn on_start(&mut self, window: &mut Window) {
+        self.tags = git::tags(self.sort);
+
+        let sort_name = if self.sort == SORT_BY_VERSION { "version" } else { "date" };
+        let mut lines = vec![
+            Line::from_str(
+                &format!("Tags: ({}, sorted by {})", self.tags.len(), sort_name),
+                Some(vec![Style::Bold, Style::Underlined])
+            ),
+            Line::empty()
+        ];
+
+        let name_width = self.tags
+            .iter()
+            .map(|t| t.name.len())
+            .max()
+            .unwrap_or(0);
+
+        lines.extend(
+            self.tags
+                .iter()
+                .map(|t| {
+                    let mut parts = vec![
+                        Part::painted(
+                            &format!("{:<width$}", t.name, width = name_width),
+                            (255, 0, 255),
+                            (0, 0, 0)
+                        ),
+                        Part::plain(" "),
+                        Part::painted(&t.target, (255, 255, 0), (0, 0, 0))
+                    ];
+
+                    if let Some(message) = &t.message {
+                        parts.push(Part::plain(&format!(" {}", message)));
+                    }
+
+                    Line::new(parts)
+                })
+        );
+
+        window.set_lines(lines);
+    }
+
+    fn register_handlers(&self, handlers: &mut KeyHandlers<TagWindow>) {
+        handlers.insert(KEY_LF, TagWindow::show_tag);
+        handlers.insert(KEY_S_LOWER, TagWindow::toggle_sort);
+        handlers.insert(KEY_D_LOWER, TagWindow::open_delete_tag_prompt);
+        handlers.insert(KEY_D_UPPER, TagWindow::open_delete_remote_tag_prompt);
+        handlers.insert(KEY_P_LOWER, TagWindow::push_tag);
+        handlers.insert(KEY_P_UPPER, TagWindow::push_tags);
+
+        register_search_handlers(handlers);
+    }
+}
+
+impl SearchableComponent<TagWindow> for TagWindow {}
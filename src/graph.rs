@@ -0,0 +1,183 @@
+use std::collections::HashSet;
+
+use crate::git::Commit;
+
+pub struct GraphCell {
+    pub symbol: char,
+    // Index of the color of the lane the symbol belongs to.
+    pub color: usize
+}
+
+// A lane waits for the commit with the hash to come up in the log.
+#[derive(Clone)]
+struct Lane {
+    hash: String,
+    color: usize
+}
+
+enum Connection {
+    // A lane ending in the commit, its branch was merged or forked here.
+    Ending,
+    // A new lane started for a parent of the commit.
+    Starting,
+    // An existing lane which also leads to a parent of the commit.
+    Joining
+}
+
+// Draws the row of the commit in the column, lanes_before and lanes_after
+// being the lanes above and below it.
+fn cells(lanes_before: &[Option<Lane>], lanes_after: &[Option<Lane>], column: usize,
+         color: usize, connections: &[(usize, Connection, usize)]) -> Vec<GraphCell> {
+    let width = lanes_before.len().max(lanes_after.len()).max(column + 1);
+
+    let left = connections.iter().map(|(i, _, _)| *i).min().unwrap_or(column).min(column);
+    let right = connections.iter().map(|(i, _, _)| *i).max().unwrap_or(column).max(column);
+
+    // The horizontal lines take the color of the farthest lane they lead to.
+    let color_at = |lane: usize| connections
+        .iter()
+        .find(|(i, _, _)| *i == lane)
+        .map(|(_, _, c)| *c);
+    let left_color = color_at(left).unwrap_or(color);
+    let right_color = color_at(right).unwrap_or(color);
+
+    let mut row = vec![];
+
+    for i in 0..width {
+        let before = lanes_before.get(i).and_then(|l| l.as_ref());
+        let after = lanes_after.get(i).and_then(|l| l.as_ref());
+        let crossed = i > left && i < right;
+
+        let (symbol, symbol_color) = if i == column {
+            ('●', color)
+        } else if let Some((_, connection, lane_color)) = connections.iter().find(|(lane, _, _)| *lane == i) {
+            // The horizontal line goes on through the lanes which are not the farthest.
+            let symbol = match (connection, crossed, i > column) {
+                (Connection::Ending, true, _) => '┴',
+                (Connection::Starting, true, _) => '┬',
+                (Connection::Joining, true, _) => '┼',
+                (Connection::Ending, false, true) => '╯',
+                (Connection::Ending, false, false) => '╰',
+                (Connection::Starting, false, true) => '╮',
+                (Connection::Starting, false, false) => '╭',
+                (Connection::Joining, false, true) => '┤',
+                (Connection::Joining, false, false) => '├'
+            };
+
+            (symbol, *lane_color)
+        } else if let (Some(_), Some(lane)) = (before, after) {
+            (if crossed { '┼' } else { '│' }, lane.color)
+        } else if crossed {
+            ('─', if i < column { left_color } else { right_color })
+        } else {
+            (' ', color)
+        };
+
+        row.push(GraphCell { symbol, color: symbol_color });
+
+        // The gap between this lane and the next one.
+        if i + 1 < width {
+            if i >= left && i < right {
+                let gap_color = if i < column { left_color } else { right_color };
+                row.push(GraphCell { symbol: '─', color: gap_color });
+            } else {
+                row.push(GraphCell { symbol: ' ', color });
+            }
+        }
+    }
+
+    row
+}
+
+// Lays out the commits, which need to be in topological order, into
+// lanes, returning one row of cells for every commit, each lane taking
+// up two cells. The commit itself is drawn as ● with its branches
+// starting and ending in it connected to it horizontally, e.g.
+//
+// ●
+// │ ●
+// ●─╯
+pub fn layout(commits: &[Commit]) -> Vec<Vec<GraphCell>> {
+    let mut lanes: Vec<Option<Lane>> = vec![];
+    let mut next_color = 0;
+    let mut rows = vec![];
+
+    // Parents filtered out of the log would keep their lanes open forever.
+    let hashes: HashSet<&str> = commits.iter().map(|c| c.hash.as_str()).collect();
+
+    let free_lane = |lanes: &mut Vec<Option<Lane>>| -> usize {
+        match lanes.iter().position(|l| l.is_none()) {
+            Some(i) => i,
+            None => {
+                lanes.push(None);
+                lanes.len() - 1
+            }
+        }
+    };
+
+    for commit in commits {
+        let parents: Vec<&String> = commit.parents
+            .iter()
+            .filter(|p| hashes.contains(p.as_str()))
+            .collect();
+
+        let expects = |l: &Option<Lane>, hash: &str| l.as_ref().is_some_and(|l| l.hash == hash);
+
+        let lanes_before = lanes.clone();
+
+        let column = match lanes.iter().position(|l| expects(l, &commit.hash)) {
+            Some(i) => i,
+            None => free_lane(&mut lanes)
+        };
+
+        let color = match &lanes[column] {
+            Some(lane) => lane.color,
+            None => {
+                next_color += 1;
+                next_color - 1
+            }
+        };
+
+        // Other branches leading to this commit end here.
+        let ending: Vec<usize> = (0..lanes.len())
+            .filter(|i| *i != column && expects(&lanes[*i], &commit.hash))
+            .collect();
+
+        let mut connections = vec![];
+
+        lanes[column] = parents
+            .first()
+            .map(|p| Lane { hash: (*p).clone(), color });
+
+        for parent in parents.iter().skip(1) {
+            match lanes.iter().position(|l| expects(l, parent)) {
+                Some(i) => {
+                    let lane_color = lanes[i].as_ref().map_or(color, |l| l.color);
+                    connections.push((i, Connection::Joining, lane_color));
+                },
+                None => {
+                    let i = free_lane(&mut lanes);
+                    lanes[i] = Some(Lane { hash: (*parent).clone(), color: next_color });
+                    connections.push((i, Connection::Starting, next_color));
+                    next_color += 1;
+                }
+            }
+        }
+
+        // Only freed after the parents got their lanes, so
+        // that a lane does not end and start in the same row.
+        for i in ending {
+            let lane_color = lanes[i].as_ref().map_or(color, |l| l.color);
+            connections.push((i, Connection::Ending, lane_color));
+            lanes[i] = None;
+        }
+
+        rows.push(cells(&lanes_before, &lanes, column, color, &connections));
+
+        while let Some(None) = lanes.last() {
+            lanes.pop();
+        }
+    }
+
+    rows
+}
@@ -4,8 +4,11 @@ use gitko_render::{Renderer, ScreenSize, Position, ScreenFactory};
 use crate::gitko::main_window::MainWindow;
 
 mod git;
+mod graph;
+mod conflict;
 mod gitko;
 mod searchable;
+mod selectable;
 
 #[allow(dead_code)]
 fn max_width() -> i32 {
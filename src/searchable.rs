@@ -1,44 +1,101 @@
 use crate::screen;
-use crate::gitko::input_window::InputWindow;
-use gitko_render::{Position, ScreenSize, KeyHandlers, Window, Component, Renderer};
+use crate::gitko::search_prompt_window::SearchPromptWindow;
+use gitko_render::{Position, ScreenSize, KeyHandlers, Window, Component, Renderer, Filter, SearchFlags};
 
-use gitko_common::ascii_table::{KEY_ETB, KEY_FORWARD_SLASH, KEY_N_LOWER, KEY_N_UPPER};
+use gitko_common::ascii_table::{KEY_ACK, KEY_ETB, KEY_FORWARD_SLASH, KEY_N_LOWER, KEY_N_UPPER};
 
 pub trait SearchableComponent<T: SearchableComponent<T> + Component<T>>: Component<T> {
-    fn term(&self) -> String;
-    fn set_term(&mut self, term: String);
-
     fn search_init(&mut self, window: &mut Window) -> bool {
-        self.set_term("".to_owned());
         window.show_cursor(true);
 
-        let mut search_window = InputWindow::new();
+        let origin = window.cursor_index();
+        let previous = window.search_pattern().cloned();
+
+        // The flags of the last search are kept for the next one.
+        let flags = previous.as_ref().map_or(SearchFlags::default(), |p| p.flags);
+        let size = ScreenSize { lines: 2, cols: window.width() };
+        let position = Position { x: 0, y: window.height() - 2 };
+
+        // Every change of the term is searched for from where the cursor was.
+        let cancelled = {
+            let mut search_window = SearchPromptWindow::new("Search", Some(flags), |pattern| {
+                window.move_cursor_to(origin);
+                window.set_search(pattern.cloned());
+                window.move_next();
+                window.redraw();
+            });
 
-        Renderer::new(
-            &mut search_window,
-            ScreenSize { lines: 2, cols: window.width() },
-            Position { x: 0, y: window.height() - 2 },
-            screen()
-        ).render();
+            Renderer::new(&mut search_window, size, position, screen()).render();
 
-        self.set_term(search_window.text);
-        window.move_next(&self.term());
+            search_window.cancelled
+        };
+
+        if cancelled {
+            window.set_search(previous);
+            window.move_cursor_to(origin);
+        }
+
+        true
+    }
+
+    // Whether the filter ranks the lines fuzzy matching the term
+    // instead of keeping the ones matching it as a search.
+    fn fuzzy_filter(&self) -> bool {
+        false
+    }
+
+    // Narrows the lines down to the ones matching the term while it is
+    // typed in, restoring the previous filter if cancelled.
+    fn filter_init(&mut self, window: &mut Window) -> bool {
+        window.show_cursor(true);
+
+        let origin = window.cursor_index();
+        let previous = window.filter().cloned();
+        let fuzzy = self.fuzzy_filter();
+
+        let flags = match previous {
+            Some(Filter::Pattern(ref pattern)) => pattern.flags,
+            _ => SearchFlags::default()
+        };
+        let size = ScreenSize { lines: 2, cols: window.width() };
+        let position = Position { x: 0, y: window.height() - 2 };
+
+        let cancelled = {
+            let flags = if fuzzy { None } else { Some(flags) };
+
+            let mut filter_window = SearchPromptWindow::new("Filter", flags, |pattern| {
+                window.set_filter(pattern.map(|p| {
+                    if fuzzy { Filter::Fuzzy(p.term.clone()) } else { Filter::Pattern(p.clone()) }
+                }));
+                window.redraw();
+            });
+
+            Renderer::new(&mut filter_window, size, position, screen()).render();
+
+            filter_window.cancelled
+        };
+
+        if cancelled {
+            window.set_filter(previous);
+            window.move_cursor_to(origin);
+        }
 
         true
     }
 
     fn next_search_result(&mut self, window: &mut Window) -> bool {
-        window.move_next(&self.term());
+        window.move_next();
         true
     }
 
     fn prev_search_result(&mut self, window: &mut Window) -> bool {
-        window.move_prev(&self.term());
+        window.move_prev();
         true
     }
 
     fn search_stop(&mut self, window: &mut Window) -> bool {
-        self.set_term("".to_owned());
+        window.set_search(None);
+        window.set_filter(None);
 
         // TODO: what if the window always shows cursor?
         window.show_cursor(false);
@@ -50,5 +107,12 @@ pub fn register_search_handlers<T: SearchableComponent<T>>(handlers: &mut KeyHan
     handlers.insert(KEY_N_LOWER, SearchableComponent::next_search_result);
     handlers.insert(KEY_N_UPPER, SearchableComponent::prev_search_result);
     handlers.insert(KEY_FORWARD_SLASH, SearchableComponent::search_init);
+
+    register_filter_handlers(handlers);
+}
+
+// For windows using n and N for other actions, filtering without searching.
+pub fn register_filter_handlers<T: SearchableComponent<T>>(handlers: &mut KeyHandlers<T>) {
+    handlers.insert(KEY_ACK, SearchableComponent::filter_init);
     handlers.insert(KEY_ETB, SearchableComponent::search_stop);
 }
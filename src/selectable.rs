@@ -0,0 +1,49 @@
+use gitko_render::{KeyHandlers, Window, Component};
+
+use gitko_common::ascii_table::{KEY_A_LOWER, KEY_SPACE, KEY_V_LOWER};
+
+pub trait SelectableComponent<T: SelectableComponent<T> + Component<T>>: Component<T> {
+    // Inclusive range of the entry lines in the section
+    // containing the line at the index, if there is one.
+    fn section_range(&self, window: &Window, index: usize) -> Option<(usize, usize)>;
+
+    // Indices of the lines an action applies to, the marked
+    // ones if there are any, else the one under the cursor.
+    fn selection(&self, window: &Window) -> Vec<usize> {
+        let marked = window.marked();
+
+        if marked.is_empty() {
+            vec![window.cursor_index()]
+        } else {
+            marked
+        }
+    }
+
+    fn toggle_mark(&mut self, window: &mut Window) -> bool {
+        let index = window.cursor_index();
+
+        window.toggle_mark(index);
+        window.move_cursor_down();
+
+        true
+    }
+
+    fn toggle_mark_section(&mut self, window: &mut Window) -> bool {
+        if let Some((start, end)) = self.section_range(window, window.cursor_index()) {
+            window.toggle_mark_range(start, end);
+        }
+
+        true
+    }
+
+    fn toggle_visual(&mut self, window: &mut Window) -> bool {
+        window.toggle_visual();
+        true
+    }
+}
+
+pub fn register_selection_handlers<T: SelectableComponent<T>>(handlers: &mut KeyHandlers<T>) {
+    handlers.insert(KEY_SPACE, SelectableComponent::toggle_mark);
+    handlers.insert(KEY_A_LOWER, SelectableComponent::toggle_mark_section);
+    handlers.insert(KEY_V_LOWER, SelectableComponent::toggle_visual);
+}